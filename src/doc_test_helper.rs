@@ -1,7 +1,7 @@
 use core::convert::Infallible;
 use core::time::Duration;
 
-use crate::CountDown;
+use crate::{AsyncDelay, CountDown};
 use embedded_hal::spi::{Operation, SpiDevice};
 
 use crate::spi::SPIInterface;
@@ -17,6 +17,11 @@ pub fn get_async_pn532() -> Pn532<SPIInterface<NoOpSPI>, ()> {
     Pn532::new(SPIInterface { spi: NoOpSPI }, ())
 }
 
+/// used for doc tests
+pub fn get_async_pn532_with_delay() -> Pn532<SPIInterface<NoOpSPI>, NoOpTimer> {
+    Pn532::new(SPIInterface { spi: NoOpSPI }, NoOpTimer)
+}
+
 pub struct NoOpSPI;
 pub struct NoOpTimer;
 
@@ -44,6 +49,22 @@ impl CountDown for NoOpTimer {
     }
 }
 
+impl AsyncDelay for NoOpTimer {
+    type Time = Duration;
+
+    async fn delay(&mut self, _: Self::Time) {}
+}
+
+/// used in AsyncDelay example implementation
+pub mod embassy_time {
+    pub struct Duration;
+    pub struct Timer;
+
+    impl Timer {
+        pub async fn after(_: Duration) {}
+    }
+}
+
 /// used in CountDown example implementation
 pub mod esp_hal {
     pub mod timer {
@@ -103,6 +103,50 @@ where
     }
 }
 
+/// Async I2C Interface with IRQ pin
+///
+/// Readiness is awaited until the IRQ pin is low instead of being polled.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Clone, Debug)]
+pub struct AsyncI2CInterfaceWithIrq<I2C, IRQ>
+where
+    I2C: embedded_hal_async::i2c::I2c,
+    IRQ: embedded_hal_async::digital::Wait<Error = Infallible>,
+{
+    pub i2c: I2C,
+    pub irq: IRQ,
+}
+
+#[cfg(feature = "async")]
+impl<I2C, IRQ> crate::AsyncInterface for AsyncI2CInterfaceWithIrq<I2C, IRQ>
+where
+    I2C: embedded_hal_async::i2c::I2c,
+    IRQ: embedded_hal_async::digital::Wait<Error = Infallible>,
+{
+    type Error = <I2C as embedded_hal::i2c::ErrorType>::Error;
+
+    async fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.write(I2C_ADDRESS, frame).await
+    }
+
+    async fn wait_ready(&mut self) -> Result<(), Self::Error> {
+        // resolves immediately if the IRQ pin is already low
+        // infallible unwrap because of IRQ bound
+        self.irq.wait_for_low().await.unwrap();
+        Ok(())
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c
+            .transaction(
+                I2C_ADDRESS,
+                &mut [Operation::Read(&mut [0]), Operation::Read(buf)],
+            )
+            .await
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -171,6 +215,44 @@ pub mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
+    impl embedded_hal_async::digital::Wait for PinMock {
+        async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+            self.mock
+                .wait_for_high()
+                .await
+                .map_err(|e| panic!("{:?}", e))
+        }
+
+        async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+            self.mock
+                .wait_for_low()
+                .await
+                .map_err(|e| panic!("{:?}", e))
+        }
+
+        async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+            self.mock
+                .wait_for_rising_edge()
+                .await
+                .map_err(|e| panic!("{:?}", e))
+        }
+
+        async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+            self.mock
+                .wait_for_falling_edge()
+                .await
+                .map_err(|e| panic!("{:?}", e))
+        }
+
+        async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+            self.mock
+                .wait_for_any_edge()
+                .await
+                .map_err(|e| panic!("{:?}", e))
+        }
+    }
+
     #[test]
     fn test_i2c_with_irq() {
         let mut i2c = I2CInterfaceWithIrq {
@@ -201,4 +283,34 @@ pub mod tests {
         i2c.i2c.done();
         i2c.irq.mock.done();
     }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_i2c_with_irq() {
+        use crate::test_helper::block_on;
+        use crate::AsyncInterface;
+
+        let mut i2c = AsyncI2CInterfaceWithIrq {
+            i2c: I2cMock::new(&[
+                // write
+                I2cTransaction::write(I2C_ADDRESS, vec![1, 2]),
+                // read
+                I2cTransaction::transaction_start(I2C_ADDRESS),
+                I2cTransaction::read(I2C_ADDRESS, vec![0]),
+                I2cTransaction::read(I2C_ADDRESS, vec![3, 4]),
+                I2cTransaction::transaction_end(I2C_ADDRESS),
+            ]),
+            irq: PinMock::new(&[DigitalTransaction::wait_for_state(State::Low)]),
+        };
+
+        block_on(i2c.write(&mut [1, 2])).unwrap();
+        block_on(i2c.wait_ready()).unwrap();
+
+        let mut buf = [0, 0];
+        block_on(i2c.read(&mut buf)).unwrap();
+        assert_eq!(buf, [3, 4]);
+
+        i2c.i2c.done();
+        i2c.irq.mock.done();
+    }
 }
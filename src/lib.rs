@@ -2,7 +2,7 @@
 //!
 //! Since communication with the Pn532 can be rather slow at times,
 //! communication can be split into multiple parts, a timeout can be provided or an async runtime
-//! can be used.
+//! can be used (optionally with an [`AsyncDelay`] for timeouts).
 //!
 //! The Pn532 supports different serial links. The [`Interface`] trait abstracts
 //! over these different links.
@@ -14,6 +14,12 @@
 //! * [`i2c::I2CInterfaceWithIrq`]
 //! * [`serialport::SerialPortInterface`]
 //!
+//! For async runtimes, the [`AsyncInterface`] trait awaits readiness instead of polling it.
+//! With the `async` feature, [`spi::AsyncSPIInterfaceWithIrq`] and [`i2c::AsyncI2CInterfaceWithIrq`]
+//! use `embedded_hal_async` traits and wait while the IRQ pin is high.
+//!
+//! To debug the traffic on the serial link, wrap the interface in a [`trace::TracingInterface`].
+//!
 //! # Troubleshooting
 //! ### General
 //! * check you're using [`Request::sam_configuration`] to initialize the PN532
@@ -32,6 +38,7 @@
 //! ```
 //! # use pn532::doc_test_helper::{NoOpSPI, NoOpTimer};
 //! use pn532::{requests::SAMMode, spi::SPIInterface, Pn532, Request};
+//! use pn532::responses::{DataIn, ListedTargets};
 //! use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
 //!
 //! # let spi = NoOpSPI;
@@ -44,10 +51,10 @@
 //! if let Err(e) = pn532.process(&Request::sam_configuration(SAMMode::Normal, false), 0, 50.ms()){
 //!     println!("Could not initialize PN532: {e:?}")
 //! }
-//! if let Ok(uid) = pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 7, 1000.ms()){
-//!     let result = pn532.process(&Request::ntag_read(10), 17, 50.ms()).unwrap();
-//!     if result[0] == 0x00 {
-//!         println!("page 10: {:?}", &result[1..5]);
+//! if let Ok(targets) = pn532.process_typed::<ListedTargets>(&Request::INLIST_ONE_ISO_A_TARGET, 20, 1000.ms()){
+//!     let page: DataIn = pn532.process_typed(&Request::ntag_read(10), 17, 50.ms()).unwrap();
+//!     if page.status.is_ok() {
+//!         println!("page 10: {:?}", &page.data[..4]);
 //!     }
 //! }
 //! ```
@@ -56,28 +63,57 @@
 //! If you want to use either [`spi::SPIInterface`] or [`spi::SPIInterfaceWithIrq`] and
 //! your peripheral cannot be set to **lsb mode** you need to enable the `msb-spi` feature of this crate.
 //!
+//! # `async` feature
+//! Enable the `async` feature to use [`spi::AsyncSPIInterfaceWithIrq`] and [`i2c::AsyncI2CInterfaceWithIrq`],
+//! which are based on `embedded_hal_async` traits.
+//!
+//! # `log` and `defmt` features
+//! Enable the `log` or `defmt` feature to use [`trace::LogObserver`] or [`trace::DefmtObserver`],
+//! which log every traced frame.
+//!
 //! # `std` feature
 //! Enable the std feature to use [`serialport::SerialPortInterface`]
 //! Only works for [targets](https://github.com/serialport/serialport-rs#platform-support) supported by the `serialport` crate.
+//!
+//! The std feature also enables [`simulator::SimulatedPn532`], an `Interface` that simulates a PN532
+//! for testing without hardware,
+//! and [`replay::RecordingInterface`] and [`replay::ReplayInterface`],
+//! which turn recorded sessions into regression tests.
 
 #![cfg_attr(not(any(feature = "std", doc, test)), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 use core::fmt::Debug;
-use core::task::Poll;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 use core::time::Duration;
 
-pub use crate::protocol::{CountDown, Error, Pn532};
+pub use crate::protocol::{AsyncDelay, CountDown, Error, Pn532};
 pub use crate::requests::Request;
+pub use crate::transaction::{Transaction, TransactionState};
 pub use nb;
 
 pub mod i2c;
+pub mod mifare;
+pub mod ndef;
+pub mod ntag;
+pub mod originality;
 mod protocol;
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod replay;
 pub mod requests;
+pub mod responses;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod serialport;
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod simulator;
 pub mod spi;
+pub mod trace;
+mod transaction;
 
 /// Abstraction over the different serial links.
 /// Either SPI, I2C or HSU (High Speed UART).
@@ -96,6 +132,26 @@ pub trait Interface {
     /// Reads data from the Pn532 into `buf`.
     /// This method will only be called if `wait_ready` returned `Poll::Ready(Ok(()))` before.
     fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
+    /// Reads a complete frame from the Pn532 into `buf` and returns the number of bytes read.
+    /// This method will only be called if `wait_ready` returned `Poll::Ready(Ok(()))` before.
+    ///
+    /// The default implementation fills all of `buf`.
+    /// Links that can read a frame in several chunks (SPI, HSU) override this method to
+    /// first read the frame header and then exactly the remaining bytes of the frame.
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.read(buf)?;
+        Ok(buf.len())
+    }
+    /// Continues reading the frame whose first `len` bytes are already in `buf`
+    /// and returns the number of bytes of the frame in `buf`.
+    ///
+    /// The default implementation reads nothing, since links like I2C restart the frame with every read.
+    /// Links that can read a frame in several chunks (SPI, HSU) override this method to
+    /// read exactly the remaining bytes of the frame.
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        let _ = buf;
+        Ok(len)
+    }
 }
 
 impl<I: Interface> Interface for &mut I {
@@ -112,10 +168,90 @@ impl<I: Interface> Interface for &mut I {
     fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
         I::read(self, buf)
     }
+
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        I::read_frame(self, buf)
+    }
+
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        I::continue_frame(self, buf, len)
+    }
+}
+
+/// Abstraction over the different serial links for use with an async runtime.
+///
+/// Every [`Interface`] is also an `AsyncInterface` whose `wait_ready` future polls
+/// [`Interface::wait_ready`] until the Pn532 is ready.
+/// Implement this trait directly if the link can await readiness,
+/// e.g. by waiting until the IRQ pin is low.
+///
+/// Provided implementations with the `async` feature:
+/// * [`spi::AsyncSPIInterfaceWithIrq`]
+/// * [`i2c::AsyncI2CInterfaceWithIrq`]
+#[allow(async_fn_in_trait)]
+pub trait AsyncInterface {
+    /// Error specific to the serial link.
+    type Error: Debug;
+    /// Writes a `frame` to the Pn532
+    ///
+    /// # Note
+    /// `frame` is passed as mutable reference to allow the SPI driver to reverse the bit order
+    /// when the `msb-spi` feature is enabled.
+    async fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error>;
+    /// Waits until the Pn532 has data to be read.
+    async fn wait_ready(&mut self) -> Result<(), Self::Error>;
+    /// Reads data from the Pn532 into `buf`.
+    /// This method will only be called after `wait_ready` returned `Ok(())`.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
+    /// Continues reading the frame whose first `len` bytes are already in `buf`
+    /// and returns the number of bytes of the frame in `buf`.
+    ///
+    /// See [`Interface::continue_frame`], the default implementation reads nothing.
+    async fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        let _ = buf;
+        Ok(len)
+    }
+}
+
+impl<I: Interface> AsyncInterface for I {
+    type Error = <I as Interface>::Error;
+
+    async fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        Interface::write(self, frame)
+    }
+
+    async fn wait_ready(&mut self) -> Result<(), Self::Error> {
+        WaitReadyFuture { interface: self }.await
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        Interface::read(self, buf)
+    }
+
+    async fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        Interface::continue_frame(self, buf, len)
+    }
+}
+
+struct WaitReadyFuture<'a, I> {
+    interface: &'a mut I,
+}
+
+impl<I: Interface> Future for WaitReadyFuture<'_, I> {
+    type Output = Result<(), I::Error>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let poll = self.interface.wait_ready();
+        if poll.is_pending() {
+            // tell the executor to poll this future again
+            cx.waker().wake_by_ref();
+        }
+        poll
+    }
 }
 
 /// Some commands return a status byte.
 /// If this byte is not zero it will contain an `ErrorCode`.
+/// [`Pn532::process_status`] strips the status byte and returns the `ErrorCode` as [`Error::Status`].
 ///
 /// ```
 /// # use pn532::ErrorCode;
@@ -267,3 +403,5 @@ impl IntoDuration for u64 {
 #[doc(hidden)]
 // FIXME: #[cfg(doctest)] once https://github.com/rust-lang/rust/issues/67295 is fixed.
 pub mod doc_test_helper;
+#[cfg(test)]
+mod test_helper;
@@ -0,0 +1,1081 @@
+//! MIFARE Classic commands
+//!
+//! [`MifareClassic`] sends the MIFARE Classic commands to the first listed target
+//! with [`Pn532::process_status`], so that a NAK of the card is returned as
+//! [`Error::Status`] with [`ErrorCode::AuthenticationError`].
+//!
+//! ```
+//! # use pn532::doc_test_helper::get_pn532;
+//! use pn532::mifare::{KeyType, MifareClassic};
+//! use pn532::Request;
+//! use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+//!
+//! let mut pn532 = get_pn532();
+//! if let Ok(&[1, ..]) = pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 10, 50.ms()) {
+//!     let uid = [0x12, 0x34, 0x56, 0x78];
+//!     let mut card = MifareClassic::new(&mut pn532, 50.ms());
+//!     card.authenticate(KeyType::A, 4, &[0xFF; 6], &uid).unwrap();
+//!     let block = card.read_block(4).unwrap();
+//! }
+//! ```
+//!
+//! [`SectorTrailer`] and [`AccessBits`] encode and decode the keys and access conditions
+//! of a sector trailer, only valid access bits can be encoded.
+//!
+//! ```
+//! use pn532::mifare::{AccessCondition, SectorTrailer};
+//!
+//! let mut trailer = SectorTrailer::TRANSPORT;
+//! // data blocks: read with key A or B, write with key B
+//! trailer.access_bits.data = [AccessCondition::new(true, false, false); 3];
+//! // trailer: key B writes the keys and the access bits
+//! trailer.access_bits.trailer = AccessCondition::new(false, true, true);
+//! trailer.key_b = [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC];
+//! assert_eq!(trailer.to_bytes()[6..9], [0x78, 0x77, 0x88]);
+//! println!("{}", trailer.access_bits.trailer.trailer_permissions());
+//! ```
+//!
+//! Increment, decrement, restore and transfer only work on blocks in the [`ValueBlock`] format.
+//!
+//! [`MifareClassic::dump`] reads a whole 1K or 4K card into a [`Dump`] by trying
+//! a list of keys for each sector, [`MifareClassic::restore_dump`] writes it to another card.
+//!
+//! ```
+//! # use pn532::doc_test_helper::get_pn532;
+//! use pn532::mifare::{CardSize, Dump, MifareClassic, RestoreOptions};
+//! use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+//!
+//! let mut pn532 = get_pn532();
+//! let uid = [0x12, 0x34, 0x56, 0x78];
+//! let default_keys = [[0xFF; 6], [0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5]];
+//! let mut card = MifareClassic::new(&mut pn532, 50.ms());
+//! let mut dump = Dump::new(CardSize::Classic1K);
+//! if card.dump(&uid, &[&default_keys], &mut dump).is_ok() {
+//!     let options = RestoreOptions {
+//!         skip_trailers: true,
+//!         skip_manufacturer_block: true,
+//!     };
+//!     let written = card.restore_dump(&uid, &dump, &[&default_keys], options);
+//! }
+//! ```
+use crate::protocol::Error;
+use crate::responses::{exact, ResponseError};
+use crate::{CountDown, ErrorCode, Interface, Pn532, Request};
+use core::fmt;
+
+/// Key used for the authentication of a sector
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum KeyType {
+    A,
+    B,
+}
+
+/// Memory size of a MIFARE Classic card
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CardSize {
+    /// 16 sectors of 4 blocks
+    Classic1K,
+    /// 32 sectors of 4 blocks and 8 sectors of 16 blocks
+    Classic4K,
+}
+
+impl CardSize {
+    pub const fn sectors(self) -> u8 {
+        match self {
+            CardSize::Classic1K => 16,
+            CardSize::Classic4K => 40,
+        }
+    }
+
+    pub const fn blocks(self) -> usize {
+        match self {
+            CardSize::Classic1K => 64,
+            CardSize::Classic4K => 256,
+        }
+    }
+}
+
+/// Sector of `block`
+pub const fn sector_of(block: u8) -> u8 {
+    if block < 128 {
+        block / 4
+    } else {
+        32 + (block - 128) / 16
+    }
+}
+
+/// First block of `sector`
+pub const fn first_block_of(sector: u8) -> u8 {
+    if sector < 32 {
+        sector * 4
+    } else {
+        128 + (sector - 32) * 16
+    }
+}
+
+/// Number of blocks of `sector` including the sector trailer
+pub const fn blocks_in_sector(sector: u8) -> u8 {
+    if sector < 32 {
+        4
+    } else {
+        16
+    }
+}
+
+/// Sector trailer of `sector`
+pub const fn trailer_block_of(sector: u8) -> u8 {
+    first_block_of(sector) + (blocks_in_sector(sector) - 1)
+}
+
+/// Keys that are allowed to perform an operation
+///
+/// Key B cannot be used for authentication if it is readable,
+/// see [`TrailerPermissions::key_b_read`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Permission {
+    Never,
+    KeyA,
+    KeyB,
+    KeyAOrB,
+}
+
+impl Permission {
+    /// Returns `true` if `key_type` is allowed to perform the operation
+    pub const fn allows(self, key_type: KeyType) -> bool {
+        matches!(
+            (self, key_type),
+            (Permission::KeyAOrB, _)
+                | (Permission::KeyA, KeyType::A)
+                | (Permission::KeyB, KeyType::B)
+        )
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Permission::Never => "never",
+            Permission::KeyA => "key A",
+            Permission::KeyB => "key B",
+            Permission::KeyAOrB => "key A|B",
+        })
+    }
+}
+
+/// Permissions of a data block
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct DataPermissions {
+    pub read: Permission,
+    pub write: Permission,
+    pub increment: Permission,
+    /// Decrement, transfer and restore
+    pub decrement: Permission,
+}
+
+impl fmt::Display for DataPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "read: {}, write: {}, increment: {}, decrement/transfer/restore: {}",
+            self.read, self.write, self.increment, self.decrement
+        )
+    }
+}
+
+/// Permissions of a sector trailer, key A can never be read
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TrailerPermissions {
+    pub key_a_write: Permission,
+    pub access_bits_read: Permission,
+    pub access_bits_write: Permission,
+    pub key_b_read: Permission,
+    pub key_b_write: Permission,
+}
+
+impl fmt::Display for TrailerPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "key A write: {}, access bits read: {}, access bits write: {}, key B read: {}, key B write: {}",
+            self.key_a_write,
+            self.access_bits_read,
+            self.access_bits_write,
+            self.key_b_read,
+            self.key_b_write
+        )
+    }
+}
+
+/// Access condition bits C1, C2 and C3 of a block
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AccessCondition(u8);
+
+impl AccessCondition {
+    pub const fn new(c1: bool, c2: bool, c3: bool) -> Self {
+        AccessCondition((c1 as u8) << 2 | (c2 as u8) << 1 | c3 as u8)
+    }
+    pub const fn c1(self) -> bool {
+        self.0 & 0b100 != 0
+    }
+    pub const fn c2(self) -> bool {
+        self.0 & 0b010 != 0
+    }
+    pub const fn c3(self) -> bool {
+        self.0 & 0b001 != 0
+    }
+
+    /// Permissions if the condition applies to a data block
+    pub const fn data_permissions(self) -> DataPermissions {
+        use Permission::*;
+        let [read, write, increment, decrement] = match self.0 {
+            0b000 => [KeyAOrB, KeyAOrB, KeyAOrB, KeyAOrB],
+            0b010 => [KeyAOrB, Never, Never, Never],
+            0b100 => [KeyAOrB, KeyB, Never, Never],
+            0b110 => [KeyAOrB, KeyB, KeyB, KeyAOrB],
+            0b001 => [KeyAOrB, Never, Never, KeyAOrB],
+            0b011 => [KeyB, KeyB, Never, Never],
+            0b101 => [KeyB, Never, Never, Never],
+            _ => [Never, Never, Never, Never],
+        };
+        DataPermissions {
+            read,
+            write,
+            increment,
+            decrement,
+        }
+    }
+
+    /// Permissions if the condition applies to a sector trailer
+    pub const fn trailer_permissions(self) -> TrailerPermissions {
+        use Permission::*;
+        let [key_a_write, access_bits_read, access_bits_write, key_b_read, key_b_write] =
+            match self.0 {
+                0b000 => [KeyA, KeyA, Never, KeyA, KeyA],
+                0b010 => [Never, KeyA, Never, KeyA, Never],
+                0b100 => [KeyB, KeyAOrB, Never, Never, KeyB],
+                0b110 => [Never, KeyAOrB, Never, Never, Never],
+                0b001 => [KeyA, KeyA, KeyA, KeyA, KeyA],
+                0b011 => [KeyB, KeyAOrB, KeyB, Never, KeyB],
+                0b101 => [Never, KeyAOrB, KeyB, Never, Never],
+                _ => [Never, KeyAOrB, Never, Never, Never],
+            };
+        TrailerPermissions {
+            key_a_write,
+            access_bits_read,
+            access_bits_write,
+            key_b_read,
+            key_b_write,
+        }
+    }
+}
+
+/// Access conditions of a sector, stored in bytes 6 to 8 of the sector trailer
+///
+/// In the sectors with 16 blocks of a MIFARE Classic 4K,
+/// each data condition applies to a group of 5 blocks.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AccessBits {
+    /// Conditions of the data blocks 0 to 2
+    pub data: [AccessCondition; 3],
+    /// Condition of the sector trailer
+    pub trailer: AccessCondition,
+}
+
+impl AccessBits {
+    /// Access bits of a new card: `FF 07 80`
+    pub const TRANSPORT: AccessBits = AccessBits {
+        data: [AccessCondition::new(false, false, false); 3],
+        trailer: AccessCondition::new(false, false, true),
+    };
+
+    /// Encodes the access bits, each bit is stored inverted as well
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        let conditions = [self.data[0], self.data[1], self.data[2], self.trailer];
+        let (mut c1, mut c2, mut c3) = (0u8, 0u8, 0u8);
+        let mut i = 0;
+        while i < 4 {
+            c1 |= (conditions[i].c1() as u8) << i;
+            c2 |= (conditions[i].c2() as u8) << i;
+            c3 |= (conditions[i].c3() as u8) << i;
+            i += 1;
+        }
+        [
+            (!c2 & 0x0F) << 4 | (!c1 & 0x0F),
+            c1 << 4 | (!c3 & 0x0F),
+            c3 << 4 | c2,
+        ]
+    }
+}
+
+/// Decodes the access bits, fails if the inverted bits do not match
+impl TryFrom<[u8; 3]> for AccessBits {
+    type Error = ();
+
+    fn try_from(bytes: [u8; 3]) -> Result<AccessBits, ()> {
+        let c1 = bytes[1] >> 4;
+        let c2 = bytes[2] & 0x0F;
+        let c3 = bytes[2] >> 4;
+        let inverted_c1 = bytes[0] & 0x0F;
+        let inverted_c2 = bytes[0] >> 4;
+        let inverted_c3 = bytes[1] & 0x0F;
+        if c1 != !inverted_c1 & 0x0F || c2 != !inverted_c2 & 0x0F || c3 != !inverted_c3 & 0x0F {
+            return Err(());
+        }
+        let condition =
+            |i: u8| AccessCondition::new(c1 >> i & 1 != 0, c2 >> i & 1 != 0, c3 >> i & 1 != 0);
+        Ok(AccessBits {
+            data: [condition(0), condition(1), condition(2)],
+            trailer: condition(3),
+        })
+    }
+}
+
+/// Last block of a sector with the keys and access bits
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SectorTrailer {
+    pub key_a: [u8; 6],
+    pub access_bits: AccessBits,
+    /// General purpose byte following the access bits
+    pub user_data: u8,
+    pub key_b: [u8; 6],
+}
+
+impl SectorTrailer {
+    /// Sector trailer of a new card: default keys `FF FF FF FF FF FF` and access bits `FF 07 80`
+    pub const TRANSPORT: SectorTrailer = SectorTrailer {
+        key_a: [0xFF; 6],
+        access_bits: AccessBits::TRANSPORT,
+        user_data: 0x69,
+        key_b: [0xFF; 6],
+    };
+
+    pub const fn to_bytes(&self) -> [u8; 16] {
+        let k = &self.key_a;
+        let b = &self.key_b;
+        let [a0, a1, a2] = self.access_bits.to_bytes();
+        [
+            k[0],
+            k[1],
+            k[2],
+            k[3],
+            k[4],
+            k[5],
+            a0,
+            a1,
+            a2,
+            self.user_data,
+            b[0],
+            b[1],
+            b[2],
+            b[3],
+            b[4],
+            b[5],
+        ]
+    }
+}
+
+/// Decodes a sector trailer, fails if the access bits are invalid
+///
+/// Note that key A always reads as zeros.
+impl TryFrom<[u8; 16]> for SectorTrailer {
+    type Error = ();
+
+    fn try_from(bytes: [u8; 16]) -> Result<SectorTrailer, ()> {
+        Ok(SectorTrailer {
+            key_a: bytes[..6].try_into().unwrap(),
+            access_bits: AccessBits::try_from([bytes[6], bytes[7], bytes[8]])?,
+            user_data: bytes[9],
+            key_b: bytes[10..].try_into().unwrap(),
+        })
+    }
+}
+
+/// Block in the value block format: the value, the inverted value and the value again,
+/// followed by the address byte, the inverted address, the address and the inverted address
+///
+/// The address is not used by the card, it is meant for a backup management.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ValueBlock {
+    pub value: i32,
+    pub address: u8,
+}
+
+impl ValueBlock {
+    pub const fn to_bytes(&self) -> [u8; 16] {
+        let value = self.value.to_le_bytes();
+        let inverted = (!self.value).to_le_bytes();
+        let address = self.address;
+        [
+            value[0],
+            value[1],
+            value[2],
+            value[3],
+            inverted[0],
+            inverted[1],
+            inverted[2],
+            inverted[3],
+            value[0],
+            value[1],
+            value[2],
+            value[3],
+            address,
+            !address,
+            address,
+            !address,
+        ]
+    }
+}
+
+/// Decodes a value block, fails if the block is not in the value block format
+impl TryFrom<[u8; 16]> for ValueBlock {
+    type Error = ();
+
+    fn try_from(bytes: [u8; 16]) -> Result<ValueBlock, ()> {
+        let value = i32::from_le_bytes(bytes[..4].try_into().unwrap());
+        let inverted = i32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        let copy = i32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        let [address, inverted_address, address_copy, inverted_address_copy] =
+            [bytes[12], bytes[13], bytes[14], bytes[15]];
+        if value != !inverted
+            || value != copy
+            || address != !inverted_address
+            || address != address_copy
+            || inverted_address != inverted_address_copy
+        {
+            return Err(());
+        }
+        Ok(ValueBlock { value, address })
+    }
+}
+
+/// Keys that authenticated a sector
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct SectorKeys {
+    pub key_a: Option<[u8; 6]>,
+    pub key_b: Option<[u8; 6]>,
+}
+
+impl SectorKeys {
+    fn set(&mut self, key_type: KeyType, key: [u8; 6]) {
+        match key_type {
+            KeyType::A => self.key_a = Some(key),
+            KeyType::B => self.key_b = Some(key),
+        }
+    }
+}
+
+/// Content of a whole card read by [`MifareClassic::dump`]
+///
+/// The sector trailers contain the keys that were found, so that a trailer is only
+/// marked as valid if key A, the access bits and key B are all known.
+///
+/// A `Dump` takes about 5 KB for both card sizes,
+/// so it is best kept in a `static` instead of on the stack.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Dump {
+    pub size: CardSize,
+    /// Blocks of the card, only the first [`CardSize::blocks`] are used
+    pub blocks: [[u8; 16]; 256],
+    /// `true` for each block that was read
+    pub valid: [bool; 256],
+    /// Keys of each sector, only the first [`CardSize::sectors`] are used
+    pub keys: [SectorKeys; 40],
+}
+
+impl Dump {
+    /// Empty dump without any valid blocks
+    pub const fn new(size: CardSize) -> Self {
+        Dump {
+            size,
+            blocks: [[0; 16]; 256],
+            valid: [false; 256],
+            keys: [SectorKeys {
+                key_a: None,
+                key_b: None,
+            }; 40],
+        }
+    }
+
+    /// Marks all blocks as invalid and forgets all keys
+    fn clear(&mut self) {
+        self.blocks.fill([0; 16]);
+        self.valid.fill(false);
+        self.keys.fill(SectorKeys::default());
+    }
+
+    /// Fills in the keys that cannot be read and checks if the trailer of `sector` is complete
+    fn complete_trailer(&mut self, sector: u8, key_type: KeyType) -> bool {
+        let block = &mut self.blocks[trailer_block_of(sector) as usize];
+        let keys = self.keys[sector as usize];
+        let Ok(access_bits) = AccessBits::try_from([block[6], block[7], block[8]]) else {
+            return false;
+        };
+        if let Some(key_a) = keys.key_a {
+            block[..6].copy_from_slice(&key_a);
+        }
+        if let Some(key_b) = keys.key_b {
+            block[10..].copy_from_slice(&key_b);
+        }
+        let key_b_read = access_bits.trailer.trailer_permissions().key_b_read;
+        keys.key_a.is_some() && (keys.key_b.is_some() || key_b_read.allows(key_type))
+    }
+}
+
+/// Options of [`MifareClassic::restore_dump`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct RestoreOptions {
+    /// Leave the keys and access bits of the card unchanged
+    pub skip_trailers: bool,
+    /// Do not write block 0, which is read-only on genuine cards
+    pub skip_manufacturer_block: bool,
+}
+
+/// MIFARE Classic card that was listed as target 1 by the [`Pn532`]
+#[derive(Debug)]
+pub struct MifareClassic<'p, I, T: CountDown, const N: usize> {
+    pn532: &'p mut Pn532<I, T, N>,
+    timeout: T::Time,
+}
+
+impl<'p, I: Interface, T: CountDown, const N: usize> MifareClassic<'p, I, T, N>
+where
+    T::Time: Clone,
+{
+    /// Sends the commands with `pn532`, each with the given `timeout`
+    pub fn new(pn532: &'p mut Pn532<I, T, N>, timeout: T::Time) -> Self {
+        MifareClassic { pn532, timeout }
+    }
+
+    /// Authenticates the sector of `block` with `key`
+    ///
+    /// `uid` is the 4 byte UID of the card, i.e. the last 4 bytes of a 7 byte UID.
+    pub fn authenticate(
+        &mut self,
+        key_type: KeyType,
+        block: u8,
+        key: &[u8; 6],
+        uid: &[u8; 4],
+    ) -> Result<(), Error<I::Error>> {
+        let request = Request::mifare_authenticate(key_type, block, key, uid);
+        self.process_no_data(&request)
+    }
+
+    /// Reads the 16 bytes of `block`
+    pub fn read_block(&mut self, block: u8) -> Result<[u8; 16], Error<I::Error>> {
+        let response =
+            self.pn532
+                .process_status(&Request::mifare_read(block), 16, self.timeout.clone())?;
+        exact(response.data).map_err(Error::Response)
+    }
+
+    /// Writes the 16 bytes of `block`
+    pub fn write_block(&mut self, block: u8, data: &[u8; 16]) -> Result<(), Error<I::Error>> {
+        self.process_no_data(&Request::mifare_write(block, data))
+    }
+
+    /// Writes `trailer` to the sector trailer `block`
+    ///
+    /// Fails with [`Error::InvalidArgument`] if `block` is not the trailer of its sector.
+    pub fn write_sector_trailer(
+        &mut self,
+        block: u8,
+        trailer: &SectorTrailer,
+    ) -> Result<(), Error<I::Error>> {
+        if block != trailer_block_of(sector_of(block)) {
+            return Err(Error::InvalidArgument);
+        }
+        self.write_block(block, &trailer.to_bytes())
+    }
+
+    /// Formats `block` as value block with `value`, the address byte is set to `block`
+    pub fn init_value_block(&mut self, block: u8, value: i32) -> Result<(), Error<I::Error>> {
+        let value_block = ValueBlock {
+            value,
+            address: block,
+        };
+        self.write_block(block, &value_block.to_bytes())
+    }
+
+    /// Reads the value block `block`
+    ///
+    /// Fails with [`ResponseError::InvalidValue`] if the block is not in the value block format.
+    pub fn read_value(&mut self, block: u8) -> Result<ValueBlock, Error<I::Error>> {
+        let bytes = self.read_block(block)?;
+        ValueBlock::try_from(bytes).map_err(|()| Error::Response(ResponseError::InvalidValue))
+    }
+
+    /// Increments the value block by `value` and transfers the result back to the block
+    ///
+    /// The block is only changed if both commands succeed.
+    pub fn increment_value(&mut self, block: u8, value: u32) -> Result<(), Error<I::Error>> {
+        self.increment(block, value)?;
+        self.transfer(block)
+    }
+
+    /// Decrements the value block by `value` and transfers the result back to the block
+    ///
+    /// The block is only changed if both commands succeed.
+    pub fn decrement_value(&mut self, block: u8, value: u32) -> Result<(), Error<I::Error>> {
+        self.decrement(block, value)?;
+        self.transfer(block)
+    }
+
+    /// Adds `value` to the value block and stores the result in the transfer buffer of the card
+    pub fn increment(&mut self, block: u8, value: u32) -> Result<(), Error<I::Error>> {
+        self.process_no_data(&Request::mifare_increment(block, value))
+    }
+
+    /// Subtracts `value` from the value block and stores the result in the transfer buffer of the card
+    pub fn decrement(&mut self, block: u8, value: u32) -> Result<(), Error<I::Error>> {
+        self.process_no_data(&Request::mifare_decrement(block, value))
+    }
+
+    /// Copies the value block into the transfer buffer of the card
+    pub fn restore(&mut self, block: u8) -> Result<(), Error<I::Error>> {
+        self.process_no_data(&Request::mifare_restore(block))
+    }
+
+    /// Writes the transfer buffer of the card to `block`
+    pub fn transfer(&mut self, block: u8) -> Result<(), Error<I::Error>> {
+        self.process_no_data(&Request::mifare_transfer(block))
+    }
+
+    /// Reads all blocks of a card of [`dump.size`](Dump::size) with the 4, 7 or 10 byte `uid` into `dump`
+    ///
+    /// `keys` contains the candidate keys for each sector, the last entry is used
+    /// for the remaining sectors. Each sector is authenticated with the first matching key A
+    /// and the first matching key B, and the blocks are read with key A and then with key B.
+    /// Blocks that cannot be read are not marked as valid in the dump.
+    ///
+    /// Failed commands halt the card, so it is activated again with InListPassiveTarget by `uid`.
+    /// `N` must be large enough for the InListPassiveTarget response.
+    ///
+    /// Fails with [`Error::InvalidArgument`] if `uid` is not 4, 7 or 10 bytes long.
+    pub fn dump(
+        &mut self,
+        uid: &[u8],
+        keys: &[&[[u8; 6]]],
+        dump: &mut Dump,
+    ) -> Result<(), Error<I::Error>> {
+        check_uid(uid)?;
+        dump.clear();
+        let mut pending = [false; 256];
+        pending[..dump.size.blocks()].fill(true);
+        for sector in 0..dump.size.sectors() {
+            let job = SectorJob {
+                uid,
+                sector,
+                keys: sector_keys(keys, sector),
+                find_all_keys: true,
+            };
+            let found =
+                self.process_sector(job, &mut pending, |card, found, key_type, block| {
+                    dump.blocks[block as usize] = card.read_block(block)?;
+                    dump.keys[sector as usize] = *found;
+                    let valid = block != trailer_block_of(sector)
+                        || dump.complete_trailer(sector, key_type);
+                    dump.valid[block as usize] = valid;
+                    Ok(valid)
+                })?;
+            // key B is also searched if the trailer was already complete with key A
+            dump.keys[sector as usize] = found;
+            if dump.valid[trailer_block_of(sector) as usize] {
+                dump.complete_trailer(sector, KeyType::A);
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the valid blocks of `dump` to the card with the 4, 7 or 10 byte `uid`
+    ///
+    /// `keys` contains the candidate keys of the card for each sector as for [`dump`](Self::dump).
+    /// The data blocks of a sector are written before the sector trailer,
+    /// which changes the keys of the card.
+    /// Returns `true` for each block that was written.
+    ///
+    /// Fails with [`Error::InvalidArgument`] before anything is written if `uid` is not 4, 7 or 10 bytes long
+    /// or if a sector trailer that would be written cannot be decoded as [`SectorTrailer`].
+    pub fn restore_dump(
+        &mut self,
+        uid: &[u8],
+        dump: &Dump,
+        keys: &[&[[u8; 6]]],
+        options: RestoreOptions,
+    ) -> Result<[bool; 256], Error<I::Error>> {
+        check_uid(uid)?;
+        let mut pending = dump.valid;
+        pending[dump.size.blocks()..].fill(false);
+        if options.skip_manufacturer_block {
+            pending[0] = false;
+        }
+        for sector in 0..dump.size.sectors() {
+            let trailer = trailer_block_of(sector) as usize;
+            if options.skip_trailers {
+                pending[trailer] = false;
+            } else if pending[trailer] && SectorTrailer::try_from(dump.blocks[trailer]).is_err() {
+                // invalid access bits would make the sector unusable
+                return Err(Error::InvalidArgument);
+            }
+        }
+        let mut written = [false; 256];
+        for sector in 0..dump.size.sectors() {
+            let job = SectorJob {
+                uid,
+                sector,
+                keys: sector_keys(keys, sector),
+                find_all_keys: false,
+            };
+            self.process_sector(job, &mut pending, |card, _, _, block| {
+                card.write_block(block, &dump.blocks[block as usize])?;
+                written[block as usize] = true;
+                Ok(true)
+            })?;
+        }
+        Ok(written)
+    }
+
+    /// Authenticates the sector of `job` with the first matching key A and then with the first matching key B
+    /// and calls `operation` for each pending block until it returns `Ok(true)`
+    ///
+    /// Key B is only searched if blocks are still pending, unless `find_all_keys` is set.
+    /// After a failed command the card is activated again and authenticated with the same key.
+    /// Returns the keys that authenticated the sector.
+    fn process_sector(
+        &mut self,
+        job: SectorJob<'_>,
+        pending: &mut [bool; 256],
+        mut operation: impl FnMut(&mut Self, &SectorKeys, KeyType, u8) -> Result<bool, Error<I::Error>>,
+    ) -> Result<SectorKeys, Error<I::Error>> {
+        let SectorJob {
+            uid,
+            sector,
+            keys,
+            find_all_keys,
+        } = job;
+        let mut found = SectorKeys::default();
+        let auth_uid: &[u8; 4] = uid[uid.len() - 4..].try_into().unwrap();
+        let first = first_block_of(sector);
+        let trailer = trailer_block_of(sector);
+        let has_pending =
+            |pending: &[bool; 256]| pending[first as usize..=trailer as usize].contains(&true);
+        for key_type in [KeyType::A, KeyType::B] {
+            if !find_all_keys && !has_pending(pending) {
+                return Ok(found);
+            }
+            let Some(key) = self.find_key(uid, key_type, trailer, keys, auth_uid)? else {
+                continue;
+            };
+            found.set(key_type, key);
+            for block in first..=trailer {
+                if !pending[block as usize] {
+                    continue;
+                }
+                match operation(self, &found, key_type, block) {
+                    Ok(done) => pending[block as usize] = !done,
+                    Err(Error::Status(_)) => {
+                        self.activate(uid)?;
+                        match self.authenticate(key_type, trailer, &key, auth_uid) {
+                            Ok(()) => {}
+                            Err(Error::Status(_)) => break,
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Returns the first of `keys` that authenticates `block` as `key_type`
+    fn find_key(
+        &mut self,
+        uid: &[u8],
+        key_type: KeyType,
+        block: u8,
+        keys: &[[u8; 6]],
+        auth_uid: &[u8; 4],
+    ) -> Result<Option<[u8; 6]>, Error<I::Error>> {
+        for key in keys {
+            match self.authenticate(key_type, block, key, auth_uid) {
+                Ok(()) => return Ok(Some(*key)),
+                Err(Error::Status(_)) => self.activate(uid)?,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Activates the halted card again, fails with [`ErrorCode::Timeout`] if it is gone
+    fn activate(&mut self, uid: &[u8]) -> Result<(), Error<I::Error>> {
+        let request = Request::try_inlist_iso_a_target_by_uid(uid)?;
+        match self.pn532.process_auto(&request, self.timeout.clone())? {
+            [0, ..] | [] => Err(Error::Status(ErrorCode::Timeout)),
+            _ => Ok(()),
+        }
+    }
+
+    fn process_no_data<const M: usize>(
+        &mut self,
+        request: &Request<M>,
+    ) -> Result<(), Error<I::Error>> {
+        self.pn532
+            .process_status(request, 0, self.timeout.clone())
+            .map(|_| ())
+    }
+}
+
+/// Sector that is processed by [`MifareClassic::process_sector`]
+struct SectorJob<'a> {
+    /// 4, 7 or 10 byte UID of the card
+    uid: &'a [u8],
+    sector: u8,
+    /// Candidate keys of the sector
+    keys: &'a [[u8; 6]],
+    /// Search key B even if no blocks are pending
+    find_all_keys: bool,
+}
+
+/// Checks that `uid` has the length of a single, double or triple size UID
+fn check_uid<E: fmt::Debug>(uid: &[u8]) -> Result<(), Error<E>> {
+    match uid.len() {
+        4 | 7 | 10 => Ok(()),
+        _ => Err(Error::InvalidArgument),
+    }
+}
+
+/// Candidate keys of `sector`, the last entry of `keys` is used for the remaining sectors
+fn sector_keys<'k>(keys: &[&'k [[u8; 6]]], sector: u8) -> &'k [[u8; 6]] {
+    match keys.get(sector as usize).or(keys.last()) {
+        Some(keys) => keys,
+        None => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc_test_helper::NoOpTimer;
+    use crate::simulator::tags::MifareClassic as VirtualCard;
+    use crate::simulator::SimulatedPn532;
+    use core::time::Duration;
+
+    const UID: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+
+    #[test]
+    fn test_value_block() {
+        let block = ValueBlock {
+            value: -2,
+            address: 0x05,
+        };
+        let bytes = [
+            0xFE, 0xFF, 0xFF, 0xFF, 0x01, 0x00, 0x00, 0x00, 0xFE, 0xFF, 0xFF, 0xFF, 0x05, 0xFA,
+            0x05, 0xFA,
+        ];
+        assert_eq!(block.to_bytes(), bytes);
+        assert_eq!(ValueBlock::try_from(bytes), Ok(block));
+        let mut invalid = bytes;
+        invalid[14] = 0x06;
+        assert_eq!(ValueBlock::try_from(invalid), Err(()));
+    }
+
+    #[test]
+    fn test_access_bits() {
+        assert_eq!(AccessBits::TRANSPORT.to_bytes(), [0xFF, 0x07, 0x80]);
+        assert_eq!(
+            AccessBits::try_from([0xFF, 0x07, 0x80]),
+            Ok(AccessBits::TRANSPORT)
+        );
+        assert_eq!(AccessBits::try_from([0xFF, 0x07, 0x81]), Err(()));
+        for bits in 0..=0xFFF {
+            let condition = |i: u16| {
+                let c = (bits >> (3 * i)) as u8;
+                AccessCondition::new(c & 4 != 0, c & 2 != 0, c & 1 != 0)
+            };
+            let access_bits = AccessBits {
+                data: [condition(0), condition(1), condition(2)],
+                trailer: condition(3),
+            };
+            assert_eq!(
+                AccessBits::try_from(access_bits.to_bytes()),
+                Ok(access_bits)
+            );
+        }
+
+        let trailer = SectorTrailer::TRANSPORT;
+        assert_eq!(SectorTrailer::try_from(trailer.to_bytes()), Ok(trailer));
+        assert_eq!(
+            trailer.access_bits.data[0].data_permissions().to_string(),
+            "read: key A|B, write: key A|B, increment: key A|B, decrement/transfer/restore: key A|B"
+        );
+        assert_eq!(
+            trailer.access_bits.trailer.trailer_permissions().to_string(),
+            "key A write: key A, access bits read: key A, access bits write: key A, key B read: key A, key B write: key A"
+        );
+    }
+
+    #[test]
+    fn test_mifare_classic() {
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(VirtualCard::new(CardSize::Classic1K, UID));
+        let mut pn532: Pn532<_, _, 32> = Pn532::new(simulator, NoOpTimer);
+        let response = pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 10, Duration::ZERO);
+        assert_eq!(response.unwrap()[0], 1);
+
+        let mut card = MifareClassic::new(&mut pn532, Duration::ZERO);
+        assert_eq!(
+            card.read_block(8),
+            Err(Error::Status(ErrorCode::AuthenticationError))
+        );
+        card.pn532
+            .process(&Request::INLIST_ONE_ISO_A_TARGET, 10, Duration::ZERO)
+            .unwrap();
+        card.authenticate(KeyType::A, 8, &[0xFF; 6], &UID).unwrap();
+        let data = [0x55; 16];
+        card.write_block(9, &data).unwrap();
+        assert_eq!(card.read_block(9), Ok(data));
+
+        card.init_value_block(8, 10).unwrap();
+        card.increment_value(8, 5).unwrap();
+        card.decrement_value(8, 3).unwrap();
+        let value = ValueBlock {
+            value: 12,
+            address: 8,
+        };
+        assert_eq!(card.read_value(8), Ok(value));
+        card.restore(8).unwrap();
+        card.transfer(10).unwrap();
+        assert_eq!(card.read_value(10), Ok(value));
+        assert_eq!(
+            card.read_value(9),
+            Err(Error::Response(ResponseError::InvalidValue))
+        );
+        // block 9 is not a value block
+        assert_eq!(
+            card.restore(9),
+            Err(Error::Status(ErrorCode::AuthenticationError))
+        );
+
+        card.pn532
+            .process(&Request::INLIST_ONE_ISO_A_TARGET, 10, Duration::ZERO)
+            .unwrap();
+        card.authenticate(KeyType::A, 11, &[0xFF; 6], &UID).unwrap();
+        let mut trailer = SectorTrailer::TRANSPORT;
+        trailer.access_bits.data = [AccessCondition::new(true, false, false); 3];
+        trailer.access_bits.trailer = AccessCondition::new(false, true, true);
+        trailer.key_b = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+        assert_eq!(
+            card.write_sector_trailer(10, &trailer),
+            Err(Error::InvalidArgument)
+        );
+        card.write_sector_trailer(11, &trailer).unwrap();
+        let read = SectorTrailer::try_from(card.read_block(11).unwrap()).unwrap();
+        assert_eq!(read.access_bits, trailer.access_bits);
+        assert_eq!(read.key_a, [0; 6]);
+        // writing requires key B now
+        assert_eq!(
+            card.write_block(10, &[0xAA; 16]),
+            Err(Error::Status(ErrorCode::AuthenticationError))
+        );
+        card.pn532
+            .process(&Request::INLIST_ONE_ISO_A_TARGET, 10, Duration::ZERO)
+            .unwrap();
+        card.authenticate(KeyType::B, 10, &trailer.key_b, &UID)
+            .unwrap();
+        card.write_block(10, &[0xAA; 16]).unwrap();
+    }
+
+    #[test]
+    fn test_dump_and_restore() {
+        use core::cell::RefCell;
+        use std::rc::Rc;
+
+        const KEY_A: [u8; 6] = [0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5];
+        const KEY_B: [u8; 6] = [0xB0, 0xB1, 0xB2, 0xB3, 0xB4, 0xB5];
+        let keys: &[&[[u8; 6]]] = &[&[[0xFF; 6], KEY_A, KEY_B]];
+
+        let mut source = VirtualCard::new(CardSize::Classic4K, UID);
+        // large sector 36 with unreadable key B
+        let mut trailer = SectorTrailer::TRANSPORT;
+        trailer.access_bits.data = [AccessCondition::new(true, false, false); 3];
+        trailer.access_bits.trailer = AccessCondition::new(false, true, true);
+        trailer.key_a = KEY_A;
+        trailer.key_b = KEY_B;
+        source.blocks_mut()[200] = [0x36; 16];
+        source.blocks_mut()[207] = trailer.to_bytes();
+        // sector 5 with unknown keys
+        let mut unknown = SectorTrailer::TRANSPORT;
+        unknown.key_a = [0x01; 6];
+        unknown.key_b = [0x02; 6];
+        source.blocks_mut()[23] = unknown.to_bytes();
+
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(source);
+        let mut pn532: Pn532<_, _, 32> = Pn532::new(simulator, NoOpTimer);
+        pn532
+            .process(&Request::INLIST_ONE_ISO_A_TARGET, 10, Duration::ZERO)
+            .unwrap();
+        let mut dump = Dump::new(CardSize::Classic4K);
+        MifareClassic::new(&mut pn532, Duration::ZERO)
+            .dump(&UID, keys, &mut dump)
+            .unwrap();
+        assert_eq!(dump.valid.iter().filter(|&&valid| !valid).count(), 4);
+        assert!(!dump.valid[20..24].contains(&true));
+        assert_eq!(dump.blocks[200], [0x36; 16]);
+        assert_eq!(dump.blocks[207], trailer.to_bytes());
+        assert_eq!(
+            dump.keys[36],
+            SectorKeys {
+                key_a: Some(KEY_A),
+                key_b: Some(KEY_B)
+            }
+        );
+        assert_eq!(dump.keys[5], SectorKeys::default());
+        assert_eq!(dump.blocks[0][..4], UID);
+
+        let target = Rc::new(RefCell::new(VirtualCard::new(CardSize::Classic4K, UID)));
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(target.clone());
+        let mut pn532: Pn532<_, _, 32> = Pn532::new(simulator, NoOpTimer);
+        pn532
+            .process(&Request::INLIST_ONE_ISO_A_TARGET, 10, Duration::ZERO)
+            .unwrap();
+        let options = RestoreOptions {
+            skip_trailers: false,
+            skip_manufacturer_block: true,
+        };
+        let written = MifareClassic::new(&mut pn532, Duration::ZERO)
+            .restore_dump(&UID, &dump, &[&[[0xFF; 6]]], options)
+            .unwrap();
+        let mut expected = dump.valid;
+        expected[0] = false;
+        assert_eq!(written, expected);
+        assert_eq!(target.borrow().blocks()[200], [0x36; 16]);
+        assert_eq!(target.borrow().blocks()[207], trailer.to_bytes());
+
+        // the restored card can be dumped with the same keys
+        let mut copy = Dump::new(CardSize::Classic4K);
+        MifareClassic::new(&mut pn532, Duration::ZERO)
+            .dump(&UID, keys, &mut copy)
+            .unwrap();
+        assert!(copy.valid.iter().all(|&valid| valid));
+        assert!((0..256).all(|i| !dump.valid[i] || copy.blocks[i] == dump.blocks[i]));
+        assert_eq!(copy.keys[36], dump.keys[36]);
+        assert_eq!(copy.keys[5].key_a, Some([0xFF; 6]));
+
+        // a corrupted trailer is rejected before anything is written
+        let mut corrupted = dump.clone();
+        corrupted.blocks[200] = [0x00; 16];
+        corrupted.blocks[207][8] ^= 0x01;
+        let mut card = MifareClassic::new(&mut pn532, Duration::ZERO);
+        assert_eq!(
+            card.restore_dump(&UID, &corrupted, keys, options),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(target.borrow().blocks()[200], [0x36; 16]);
+        assert_eq!(
+            card.dump(&UID[..3], keys, &mut copy),
+            Err(Error::InvalidArgument)
+        );
+    }
+}
@@ -0,0 +1,1168 @@
+//! NFC Data Exchange Format
+//!
+//! [`Records`] parses the records of an NDEF message without copying,
+//! [`Record::content`] decodes the well-known URI, Text and Smart Poster records
+//! as well as MIME media and NFC Forum external type records.
+//! [`MessageWriter`] builds a message in a buffer and sets the message begin and end flags.
+//!
+//! ```
+//! use pn532::ndef::{MessageWriter, RecordContent, Records, TextRecord, UriRecord};
+//!
+//! let mut buf = [0; 64];
+//! let mut writer = MessageWriter::new(&mut buf);
+//! writer.push_typed(&UriRecord::new("https://www.example.com")).unwrap();
+//! writer.push_typed(&TextRecord::utf8("en", "Example")).unwrap();
+//! let message = writer.finish().unwrap();
+//!
+//! for record in Records::new(message) {
+//!     match record.unwrap().content().unwrap() {
+//!         RecordContent::Uri(uri) => println!("{uri}"),
+//!         RecordContent::Text(text) => println!("{}", text.text),
+//!         _ => {}
+//!     }
+//! }
+//! ```
+//!
+//! On NFC Forum Type 2 tags like the NTAG21x, the message is stored in an NDEF Message TLV
+//! in the user memory, see [`find_message_tlv`] and [`encode_message_tlv`].
+//!
+//! Chunked payloads are not reassembled, the [`chunked`](Record::chunked) flag is set on all but the last chunk.
+use core::fmt;
+
+const MB: u8 = 0x80;
+const ME: u8 = 0x40;
+const CF: u8 = 0x20;
+const SR: u8 = 0x10;
+const IL: u8 = 0x08;
+
+/// Could not parse or build an NDEF message
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum NdefError {
+    /// The data ends within a record or before the last record of the message
+    Truncated,
+    /// The record header contains an invalid combination of fields
+    InvalidRecord,
+    /// The payload of a typed record is invalid
+    InvalidPayload,
+    /// The record does not fit into the buffer
+    BufTooSmall,
+}
+
+/// Type Name Format: how the record type is to be interpreted
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(u8)]
+pub enum Tnf {
+    /// No type, ID or payload
+    Empty = 0x00,
+    /// NFC Forum well-known type, e.g. `U` or `T`
+    WellKnown = 0x01,
+    /// Media type as defined in RFC 2046, e.g. `text/plain`
+    MimeMedia = 0x02,
+    /// Absolute URI as defined in RFC 3986
+    AbsoluteUri = 0x03,
+    /// NFC Forum external type `domain:type`
+    External = 0x04,
+    /// Payload of unknown type
+    Unknown = 0x05,
+    /// Middle or terminating chunk of a chunked payload
+    Unchanged = 0x06,
+    Reserved = 0x07,
+}
+
+impl From<u8> for Tnf {
+    /// Decodes the lower 3 bits
+    fn from(value: u8) -> Self {
+        match value & 0x07 {
+            0x00 => Tnf::Empty,
+            0x01 => Tnf::WellKnown,
+            0x02 => Tnf::MimeMedia,
+            0x03 => Tnf::AbsoluteUri,
+            0x04 => Tnf::External,
+            0x05 => Tnf::Unknown,
+            0x06 => Tnf::Unchanged,
+            _ => Tnf::Reserved,
+        }
+    }
+}
+
+/// A single NDEF record
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Record<'a> {
+    /// MB: first record of the message, set by [`MessageWriter`]
+    pub message_begin: bool,
+    /// ME: last record of the message, set by [`MessageWriter`]
+    pub message_end: bool,
+    /// CF: the payload continues in the next record
+    pub chunked: bool,
+    pub tnf: Tnf,
+    pub record_type: &'a [u8],
+    pub id: Option<&'a [u8]>,
+    pub payload: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    pub const fn new(tnf: Tnf, record_type: &'a [u8], payload: &'a [u8]) -> Self {
+        Record {
+            message_begin: false,
+            message_end: false,
+            chunked: false,
+            tnf,
+            record_type,
+            id: None,
+            payload,
+        }
+    }
+
+    /// Parses the record at the start of `data` and returns it with the remaining data
+    pub fn parse(data: &'a [u8]) -> Result<(Record<'a>, &'a [u8]), NdefError> {
+        let [header, type_len, rest @ ..] = data else {
+            return Err(NdefError::Truncated);
+        };
+        let (payload_len, rest) = if header & SR != 0 {
+            let [len, rest @ ..] = rest else {
+                return Err(NdefError::Truncated);
+            };
+            (*len as usize, rest)
+        } else {
+            let [b0, b1, b2, b3, rest @ ..] = rest else {
+                return Err(NdefError::Truncated);
+            };
+            (u32::from_be_bytes([*b0, *b1, *b2, *b3]) as usize, rest)
+        };
+        let (id_len, rest) = if header & IL != 0 {
+            let [len, rest @ ..] = rest else {
+                return Err(NdefError::Truncated);
+            };
+            (Some(*len as usize), rest)
+        } else {
+            (None, rest)
+        };
+        let (record_type, rest) = split(rest, *type_len as usize)?;
+        let (id, rest) = match id_len {
+            Some(len) => split(rest, len).map(|(id, rest)| (Some(id), rest))?,
+            None => (None, rest),
+        };
+        let (payload, rest) = split(rest, payload_len)?;
+
+        let tnf = Tnf::from(*header);
+        check_record(*header, tnf, record_type, id, payload.len())?;
+        let record = Record {
+            message_begin: header & MB != 0,
+            message_end: header & ME != 0,
+            chunked: header & CF != 0,
+            tnf,
+            record_type,
+            id,
+            payload,
+        };
+        Ok((record, rest))
+    }
+
+    /// Number of bytes of the encoded record, short records are used for payloads up to 255 bytes
+    pub fn encoded_len(&self) -> usize {
+        header_len(self.payload.len(), self.id) + self.record_type.len() + self.payload.len()
+    }
+
+    /// Encodes the record into `buf` and returns the number of bytes written
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, NdefError> {
+        let flags = (self.message_begin as u8 * MB)
+            | (self.message_end as u8 * ME)
+            | (self.chunked as u8 * CF);
+        check_record(
+            flags,
+            self.tnf,
+            self.record_type,
+            self.id,
+            self.payload.len(),
+        )?;
+        encode_record(
+            buf,
+            flags,
+            self.tnf,
+            self.record_type,
+            self.id,
+            self.payload.len(),
+            |payload| payload.copy_from_slice(self.payload),
+        )
+    }
+
+    /// Decodes the payload depending on the TNF and the record type
+    pub fn content(&self) -> Result<RecordContent<'a>, NdefError> {
+        let content = match (self.tnf, self.record_type) {
+            (Tnf::WellKnown, b"U") => RecordContent::Uri(UriRecord::parse(self.payload)?),
+            (Tnf::WellKnown, b"T") => RecordContent::Text(TextRecord::parse(self.payload)?),
+            (Tnf::WellKnown, b"Sp") => {
+                RecordContent::SmartPoster(SmartPoster::parse(self.payload)?)
+            }
+            (Tnf::MimeMedia, media_type) => RecordContent::Mime(MimeRecord {
+                media_type: utf8(media_type)?,
+                data: self.payload,
+            }),
+            (Tnf::External, record_type) => RecordContent::External(ExternalRecord {
+                record_type: utf8(record_type)?,
+                data: self.payload,
+            }),
+            _ => RecordContent::Other,
+        };
+        Ok(content)
+    }
+}
+
+/// Decoded payload of a [`Record`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RecordContent<'a> {
+    Uri(UriRecord<'a>),
+    Text(TextRecord<'a>),
+    SmartPoster(SmartPoster<'a>),
+    Mime(MimeRecord<'a>),
+    External(ExternalRecord<'a>),
+    /// Any other record, e.g. an empty record or a chunk
+    Other,
+}
+
+/// Iterator over the records of an NDEF message
+///
+/// Stops after the record with the message end flag, fails with [`NdefError::Truncated`]
+/// if the data ends before and with [`NdefError::InvalidRecord`] if a chunk is missing its
+/// continuation or a continuation is missing its first chunk.
+#[derive(Clone, Debug)]
+pub struct Records<'a> {
+    data: &'a [u8],
+    done: bool,
+    in_chunk: bool,
+}
+
+impl<'a> Records<'a> {
+    pub fn new(message: &'a [u8]) -> Self {
+        Records {
+            data: message,
+            done: false,
+            in_chunk: false,
+        }
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Record<'a>, NdefError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = Record::parse(self.data).and_then(|(record, rest)| {
+            check_chunk(self.in_chunk, record.tnf)?;
+            Ok((record, rest))
+        });
+        match result {
+            Ok((record, rest)) => {
+                self.data = rest;
+                self.done = record.message_end;
+                self.in_chunk = record.chunked;
+                Some(Ok(record))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Record with a typed payload that can be written by [`MessageWriter::push_typed`]
+pub trait TypedRecord {
+    fn tnf(&self) -> Tnf;
+    fn record_type(&self) -> &[u8];
+    fn payload_len(&self) -> usize;
+    /// Checks that the payload can be written, [`MessageWriter`] calls this before
+    /// [`write_payload`](TypedRecord::write_payload)
+    fn validate(&self) -> Result<(), NdefError> {
+        Ok(())
+    }
+    /// Writes the payload into `buf`, which has exactly [`payload_len`](TypedRecord::payload_len) bytes
+    fn write_payload(&self, buf: &mut [u8]);
+}
+
+/// Builds an NDEF message in a buffer
+///
+/// The message begin flag is set on the first record and the message end flag on the last record.
+/// Records that violate the constraints of their TNF or of chunking are rejected
+/// with [`NdefError::InvalidRecord`].
+#[derive(Debug)]
+pub struct MessageWriter<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+    last_header: Option<usize>,
+    in_chunk: bool,
+}
+
+impl<'b> MessageWriter<'b> {
+    pub fn new(buf: &'b mut [u8]) -> Self {
+        MessageWriter {
+            buf,
+            len: 0,
+            last_header: None,
+            in_chunk: false,
+        }
+    }
+
+    /// Appends `record`, its message begin and end flags are ignored
+    pub fn push(&mut self, record: &Record<'_>) -> Result<(), NdefError> {
+        let flags = record.chunked as u8 * CF;
+        self.push_with(
+            flags,
+            record.tnf,
+            record.record_type,
+            record.id,
+            record.payload.len(),
+            |payload| payload.copy_from_slice(record.payload),
+        )
+    }
+
+    /// Appends a typed record with the optional `id`
+    ///
+    /// Fails with the error of [`TypedRecord::validate`] without writing anything.
+    pub fn push_typed_with_id(
+        &mut self,
+        record: &impl TypedRecord,
+        id: Option<&[u8]>,
+    ) -> Result<(), NdefError> {
+        record.validate()?;
+        self.push_with(
+            0,
+            record.tnf(),
+            record.record_type(),
+            id,
+            record.payload_len(),
+            |payload| record.write_payload(payload),
+        )
+    }
+
+    /// Appends a typed record
+    pub fn push_typed(&mut self, record: &impl TypedRecord) -> Result<(), NdefError> {
+        self.push_typed_with_id(record, None)
+    }
+
+    /// Returns the message, a message without records consists of an empty record
+    ///
+    /// Fails with [`NdefError::InvalidRecord`] if the last record is a chunk without continuation.
+    pub fn finish(self) -> Result<&'b [u8], NdefError> {
+        if self.in_chunk {
+            return Err(NdefError::InvalidRecord);
+        }
+        if self.last_header.is_none() {
+            let len = Record::new(Tnf::Empty, &[], &[]).encode(self.buf)?;
+            self.buf[0] |= MB | ME;
+            return Ok(&self.buf[..len]);
+        }
+        Ok(&self.buf[..self.len])
+    }
+
+    fn push_with(
+        &mut self,
+        flags: u8,
+        tnf: Tnf,
+        record_type: &[u8],
+        id: Option<&[u8]>,
+        payload_len: usize,
+        write_payload: impl FnOnce(&mut [u8]),
+    ) -> Result<(), NdefError> {
+        let flags = match self.last_header {
+            Some(_) => flags,
+            None => flags | MB,
+        };
+        check_record(flags, tnf, record_type, id, payload_len)?;
+        check_chunk(self.in_chunk, tnf)?;
+        let written = encode_record(
+            &mut self.buf[self.len..],
+            flags | ME,
+            tnf,
+            record_type,
+            id,
+            payload_len,
+            write_payload,
+        )?;
+        if let Some(last_header) = self.last_header {
+            self.buf[last_header] &= !ME;
+        }
+        self.last_header = Some(self.len);
+        self.len += written;
+        self.in_chunk = flags & CF != 0;
+        Ok(())
+    }
+}
+
+/// URI identifier codes of the URI record type definition
+const URI_PREFIXES: [&str; 36] = [
+    "",
+    "http://www.",
+    "https://www.",
+    "http://",
+    "https://",
+    "tel:",
+    "mailto:",
+    "ftp://anonymous:anonymous@",
+    "ftp://ftp.",
+    "ftps://",
+    "sftp://",
+    "smb://",
+    "nfs://",
+    "ftp://",
+    "dav://",
+    "news:",
+    "telnet://",
+    "imap:",
+    "rtsp://",
+    "urn:",
+    "pop:",
+    "sip:",
+    "sips:",
+    "tftp:",
+    "btspp://",
+    "btl2cap://",
+    "btgoep://",
+    "tcpobex://",
+    "irdaobex://",
+    "file://",
+    "urn:epc:id:",
+    "urn:epc:tag:",
+    "urn:epc:pat:",
+    "urn:epc:raw:",
+    "urn:epc:",
+    "urn:nfc:",
+];
+
+/// Well-known URI record `U`, the URI is abbreviated with an identifier code for its prefix
+///
+/// `Display` writes the complete URI.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct UriRecord<'a> {
+    /// URI identifier code, reserved codes are treated as no prefix
+    pub prefix_code: u8,
+    /// URI without the prefix
+    pub rest: &'a str,
+}
+
+impl<'a> UriRecord<'a> {
+    /// Abbreviates `uri` with the longest matching prefix
+    pub fn new(uri: &'a str) -> Self {
+        let mut record = UriRecord {
+            prefix_code: 0,
+            rest: uri,
+        };
+        for (code, prefix) in URI_PREFIXES.iter().enumerate() {
+            if let Some(rest) = uri.strip_prefix(prefix) {
+                if rest.len() < record.rest.len() {
+                    record = UriRecord {
+                        prefix_code: code as u8,
+                        rest,
+                    };
+                }
+            }
+        }
+        record
+    }
+
+    pub fn parse(payload: &'a [u8]) -> Result<Self, NdefError> {
+        let [prefix_code, rest @ ..] = payload else {
+            return Err(NdefError::InvalidPayload);
+        };
+        Ok(UriRecord {
+            prefix_code: *prefix_code,
+            rest: utf8(rest)?,
+        })
+    }
+
+    /// The prefix of the identifier code
+    pub fn prefix(&self) -> &'static str {
+        URI_PREFIXES
+            .get(self.prefix_code as usize)
+            .copied()
+            .unwrap_or("")
+    }
+}
+
+impl fmt::Display for UriRecord<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.prefix())?;
+        f.write_str(self.rest)
+    }
+}
+
+impl TypedRecord for UriRecord<'_> {
+    fn tnf(&self) -> Tnf {
+        Tnf::WellKnown
+    }
+    fn record_type(&self) -> &[u8] {
+        b"U"
+    }
+    fn payload_len(&self) -> usize {
+        1 + self.rest.len()
+    }
+    fn write_payload(&self, buf: &mut [u8]) {
+        buf[0] = self.prefix_code;
+        buf[1..].copy_from_slice(self.rest.as_bytes());
+    }
+}
+
+/// Encoded text of a [`TextRecord`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Text<'a> {
+    Utf8(&'a str),
+    /// UTF-16 encoded bytes, big endian unless they start with a byte order mark
+    Utf16(&'a [u8]),
+}
+
+impl<'a> Text<'a> {
+    /// Encodes `text` as UTF-16 big endian into `buf`
+    pub fn utf16_from_str(text: &str, buf: &'a mut [u8]) -> Result<Self, NdefError> {
+        let mut len = 0;
+        for unit in text.encode_utf16() {
+            let bytes = buf.get_mut(len..len + 2).ok_or(NdefError::BufTooSmall)?;
+            bytes.copy_from_slice(&unit.to_be_bytes());
+            len += 2;
+        }
+        Ok(Text::Utf16(&buf[..len]))
+    }
+
+    /// Decodes the text, invalid UTF-16 and an odd trailing byte are replaced with `U+FFFD`
+    pub fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        let (utf8, utf16) = match *self {
+            Text::Utf8(text) => (Some(text.chars()), None),
+            Text::Utf16(bytes) => {
+                let (little_endian, bytes) = match bytes {
+                    [0xFF, 0xFE, rest @ ..] => (true, rest),
+                    [0xFE, 0xFF, rest @ ..] => (false, rest),
+                    _ => (false, bytes),
+                };
+                let units = bytes.chunks_exact(2);
+                let odd_byte = !units.remainder().is_empty();
+                let units = units.map(move |unit| {
+                    let unit = [unit[0], unit[1]];
+                    if little_endian {
+                        u16::from_le_bytes(unit)
+                    } else {
+                        u16::from_be_bytes(unit)
+                    }
+                });
+                let chars = char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .chain(odd_byte.then_some(char::REPLACEMENT_CHARACTER));
+                (None, Some(chars))
+            }
+        };
+        utf8.into_iter()
+            .flatten()
+            .chain(utf16.into_iter().flatten())
+    }
+}
+
+impl fmt::Display for Text<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Text::Utf8(text) => f.write_str(text),
+            Text::Utf16(_) => self.chars().try_for_each(|c| fmt::Write::write_char(f, c)),
+        }
+    }
+}
+
+/// Well-known Text record `T`
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TextRecord<'a> {
+    /// IANA language code, e.g. `en` or `de-CH`
+    pub language: &'a str,
+    pub text: Text<'a>,
+}
+
+impl<'a> TextRecord<'a> {
+    pub const fn utf8(language: &'a str, text: &'a str) -> Self {
+        TextRecord {
+            language,
+            text: Text::Utf8(text),
+        }
+    }
+
+    pub fn parse(payload: &'a [u8]) -> Result<Self, NdefError> {
+        let [status, rest @ ..] = payload else {
+            return Err(NdefError::InvalidPayload);
+        };
+        let (language, text) =
+            split(rest, (status & 0x3F) as usize).map_err(|_| NdefError::InvalidPayload)?;
+        let text = if status & 0x80 != 0 {
+            Text::Utf16(text)
+        } else {
+            Text::Utf8(utf8(text)?)
+        };
+        let record = TextRecord {
+            language: utf8(language)?,
+            text,
+        };
+        record.validate()?;
+        Ok(record)
+    }
+
+    fn text_bytes(&self) -> &[u8] {
+        match self.text {
+            Text::Utf8(text) => text.as_bytes(),
+            Text::Utf16(bytes) => bytes,
+        }
+    }
+}
+
+impl TypedRecord for TextRecord<'_> {
+    fn tnf(&self) -> Tnf {
+        Tnf::WellKnown
+    }
+    fn record_type(&self) -> &[u8] {
+        b"T"
+    }
+    fn payload_len(&self) -> usize {
+        1 + self.language.len() + self.text_bytes().len()
+    }
+    /// Fails with [`NdefError::InvalidPayload`] if the language code is empty, not ASCII
+    /// or longer than 63 bytes, or if UTF-16 text has an odd number of bytes
+    fn validate(&self) -> Result<(), NdefError> {
+        let language_valid = (1..=0x3F).contains(&self.language.len()) && self.language.is_ascii();
+        let text_valid = match self.text {
+            Text::Utf8(_) => true,
+            Text::Utf16(bytes) => bytes.len() % 2 == 0,
+        };
+        if !language_valid || !text_valid {
+            return Err(NdefError::InvalidPayload);
+        }
+        Ok(())
+    }
+    fn write_payload(&self, buf: &mut [u8]) {
+        let utf16 = matches!(self.text, Text::Utf16(_));
+        buf[0] = (utf16 as u8) << 7 | self.language.len() as u8;
+        let (language, text) = buf[1..].split_at_mut(self.language.len());
+        language.copy_from_slice(self.language.as_bytes());
+        text.copy_from_slice(self.text_bytes());
+    }
+}
+
+/// Recommended action of a [`SmartPoster`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Action {
+    /// Do the action, e.g. open the URI
+    Do = 0x00,
+    /// Save for later
+    Save = 0x01,
+    /// Open for editing
+    Edit = 0x02,
+}
+
+/// Well-known Smart Poster record `Sp`
+///
+/// The payload is an NDEF message with exactly one URI record and optional records
+/// like titles in different languages and the recommended action.
+/// To build a smart poster, write the nested message with a [`MessageWriter`] first.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SmartPoster<'a> {
+    pub message: &'a [u8],
+}
+
+impl<'a> SmartPoster<'a> {
+    /// Checks that the nested message is valid and contains exactly one URI record
+    pub fn parse(payload: &'a [u8]) -> Result<Self, NdefError> {
+        let poster = SmartPoster { message: payload };
+        let mut uris = 0;
+        for record in Records::new(payload) {
+            let record = record?;
+            if record.tnf == Tnf::WellKnown && record.record_type == b"U" {
+                UriRecord::parse(record.payload)?;
+                uris += 1;
+            }
+        }
+        if uris != 1 {
+            return Err(NdefError::InvalidPayload);
+        }
+        Ok(poster)
+    }
+
+    pub fn uri(&self) -> Option<UriRecord<'a>> {
+        self.records().find_map(|record| match record.content() {
+            Ok(RecordContent::Uri(uri)) => Some(uri),
+            _ => None,
+        })
+    }
+
+    /// Titles in different languages
+    pub fn titles(&self) -> impl Iterator<Item = TextRecord<'a>> + 'a {
+        self.records().filter_map(|record| match record.content() {
+            Ok(RecordContent::Text(text)) => Some(text),
+            _ => None,
+        })
+    }
+
+    pub fn action(&self) -> Option<Action> {
+        self.records().find_map(
+            |record| match (record.tnf, record.record_type, record.payload) {
+                (Tnf::WellKnown, b"act", [0x00]) => Some(Action::Do),
+                (Tnf::WellKnown, b"act", [0x01]) => Some(Action::Save),
+                (Tnf::WellKnown, b"act", [0x02]) => Some(Action::Edit),
+                _ => None,
+            },
+        )
+    }
+
+    fn records(&self) -> impl Iterator<Item = Record<'a>> + 'a {
+        Records::new(self.message).map_while(Result::ok)
+    }
+}
+
+impl TypedRecord for SmartPoster<'_> {
+    fn tnf(&self) -> Tnf {
+        Tnf::WellKnown
+    }
+    fn record_type(&self) -> &[u8] {
+        b"Sp"
+    }
+    fn payload_len(&self) -> usize {
+        self.message.len()
+    }
+    fn write_payload(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(self.message);
+    }
+}
+
+/// Action record `act` of a [`SmartPoster`]
+impl TypedRecord for Action {
+    fn tnf(&self) -> Tnf {
+        Tnf::WellKnown
+    }
+    fn record_type(&self) -> &[u8] {
+        b"act"
+    }
+    fn payload_len(&self) -> usize {
+        1
+    }
+    fn write_payload(&self, buf: &mut [u8]) {
+        buf[0] = *self as u8;
+    }
+}
+
+/// MIME media record
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct MimeRecord<'a> {
+    /// e.g. `text/plain` or `application/vnd.bluetooth.ep.oob`
+    pub media_type: &'a str,
+    pub data: &'a [u8],
+}
+
+impl TypedRecord for MimeRecord<'_> {
+    fn tnf(&self) -> Tnf {
+        Tnf::MimeMedia
+    }
+    fn record_type(&self) -> &[u8] {
+        self.media_type.as_bytes()
+    }
+    fn payload_len(&self) -> usize {
+        self.data.len()
+    }
+    fn write_payload(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(self.data);
+    }
+}
+
+/// NFC Forum external type record
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ExternalRecord<'a> {
+    /// Domain name and type, e.g. `example.com:mytype`
+    pub record_type: &'a str,
+    pub data: &'a [u8],
+}
+
+impl TypedRecord for ExternalRecord<'_> {
+    fn tnf(&self) -> Tnf {
+        Tnf::External
+    }
+    fn record_type(&self) -> &[u8] {
+        self.record_type.as_bytes()
+    }
+    fn payload_len(&self) -> usize {
+        self.data.len()
+    }
+    fn write_payload(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(self.data);
+    }
+}
+
+const NULL_TLV: u8 = 0x00;
+const MESSAGE_TLV: u8 = 0x03;
+const TERMINATOR_TLV: u8 = 0xFE;
+
+/// Finds the NDEF Message TLV in the data area of a Type 2 tag, i.e. starting at page 4,
+/// and returns the NDEF message
+///
+/// Returns `None` if the terminator TLV or the end of `data` is reached first.
+pub fn find_message_tlv(data: &[u8]) -> Result<Option<&[u8]>, NdefError> {
+    let mut data = data;
+    loop {
+        let (tag, rest) = match data {
+            [] | [TERMINATOR_TLV, ..] => return Ok(None),
+            [NULL_TLV, rest @ ..] => {
+                data = rest;
+                continue;
+            }
+            [tag, rest @ ..] => (*tag, rest),
+        };
+        let (len, rest) = match rest {
+            [0xFF, msb, lsb, rest @ ..] => (u16::from_be_bytes([*msb, *lsb]) as usize, rest),
+            [0xFF, ..] | [] => return Err(NdefError::Truncated),
+            [len, rest @ ..] => (*len as usize, rest),
+        };
+        let (value, rest) = split(rest, len)?;
+        if tag == MESSAGE_TLV {
+            return Ok(Some(value));
+        }
+        data = rest;
+    }
+}
+
+/// Encodes `message` in an NDEF Message TLV followed by a terminator TLV
+/// and returns the number of bytes written
+pub fn encode_message_tlv(message: &[u8], buf: &mut [u8]) -> Result<usize, NdefError> {
+    let len_bytes = if message.len() < 0xFF { 1 } else { 3 };
+    let total = 1 + len_bytes + message.len() + 1;
+    if message.len() > 0xFFFE || buf.len() < total {
+        return Err(NdefError::BufTooSmall);
+    }
+    buf[0] = MESSAGE_TLV;
+    if len_bytes == 1 {
+        buf[1] = message.len() as u8;
+    } else {
+        buf[1] = 0xFF;
+        buf[2..4].copy_from_slice(&(message.len() as u16).to_be_bytes());
+    }
+    buf[1 + len_bytes..total - 1].copy_from_slice(message);
+    buf[total - 1] = TERMINATOR_TLV;
+    Ok(total)
+}
+
+fn split(data: &[u8], len: usize) -> Result<(&[u8], &[u8]), NdefError> {
+    if data.len() < len {
+        return Err(NdefError::Truncated);
+    }
+    Ok(data.split_at(len))
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str, NdefError> {
+    core::str::from_utf8(bytes).map_err(|_| NdefError::InvalidPayload)
+}
+
+/// Checks the constraints of the TNF and the chunk flag of a single record with the header `flags`
+fn check_record(
+    flags: u8,
+    tnf: Tnf,
+    record_type: &[u8],
+    id: Option<&[u8]>,
+    payload_len: usize,
+) -> Result<(), NdefError> {
+    let valid = match tnf {
+        Tnf::Empty => record_type.is_empty() && id.is_none() && payload_len == 0,
+        Tnf::Unknown => record_type.is_empty(),
+        // middle and last chunks, which cannot begin a message
+        Tnf::Unchanged => record_type.is_empty() && id.is_none() && flags & MB == 0,
+        Tnf::Reserved => false,
+        _ => !record_type.is_empty(),
+    };
+    // the last record of a message cannot be continued
+    if !valid || flags & (CF | ME) == CF | ME {
+        return Err(NdefError::InvalidRecord);
+    }
+    Ok(())
+}
+
+/// Checks that only the records after a chunk use [`Tnf::Unchanged`]
+fn check_chunk(in_chunk: bool, tnf: Tnf) -> Result<(), NdefError> {
+    if in_chunk != (tnf == Tnf::Unchanged) {
+        return Err(NdefError::InvalidRecord);
+    }
+    Ok(())
+}
+
+fn header_len(payload_len: usize, id: Option<&[u8]>) -> usize {
+    let payload_len_bytes = if payload_len <= 0xFF { 1 } else { 4 };
+    let id_len_bytes = id.map_or(0, |id| 1 + id.len());
+    2 + payload_len_bytes + id_len_bytes
+}
+
+fn encode_record(
+    buf: &mut [u8],
+    flags: u8,
+    tnf: Tnf,
+    record_type: &[u8],
+    id: Option<&[u8]>,
+    payload_len: usize,
+    write_payload: impl FnOnce(&mut [u8]),
+) -> Result<usize, NdefError> {
+    let id_len = id.map_or(0, <[u8]>::len);
+    if record_type.len() > 0xFF || id_len > 0xFF || payload_len > u32::MAX as usize {
+        return Err(NdefError::InvalidRecord);
+    }
+    let header_len = header_len(payload_len, id);
+    let total = header_len + record_type.len() + payload_len;
+    if buf.len() < total {
+        return Err(NdefError::BufTooSmall);
+    }
+    let short = payload_len <= 0xFF;
+    buf[0] = flags | (short as u8 * SR) | (id.is_some() as u8 * IL) | tnf as u8;
+    buf[1] = record_type.len() as u8;
+    let mut i = 2;
+    if short {
+        buf[i] = payload_len as u8;
+        i += 1;
+    } else {
+        buf[i..i + 4].copy_from_slice(&(payload_len as u32).to_be_bytes());
+        i += 4;
+    }
+    if id.is_some() {
+        buf[i] = id_len as u8;
+        i += 1;
+    }
+    buf[i..i + record_type.len()].copy_from_slice(record_type);
+    i += record_type.len();
+    if let Some(id) = id {
+        buf[i..i + id.len()].copy_from_slice(id);
+        i += id.len();
+    }
+    write_payload(&mut buf[i..total]);
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_record() {
+        // https://www.example.com
+        let message = [
+            0xD1, 0x01, 0x0C, 0x55, 0x02, b'e', b'x', b'a', b'm', b'p', b'l', b'e', b'.', b'c',
+            b'o', b'm',
+        ];
+        let mut records = Records::new(&message);
+        let record = records.next().unwrap().unwrap();
+        assert!(records.next().is_none());
+        assert!(record.message_begin && record.message_end);
+        let RecordContent::Uri(uri) = record.content().unwrap() else {
+            panic!("expected a URI record");
+        };
+        assert_eq!(uri.prefix(), "https://www.");
+        assert_eq!(uri.to_string(), "https://www.example.com");
+        assert_eq!(UriRecord::new("https://www.example.com"), uri);
+        assert_eq!(UriRecord::new("urn:epc:tag:1234").prefix_code, 0x1F);
+        assert_eq!(UriRecord::new("geo:47.37,8.54").prefix_code, 0x00);
+
+        let mut buf = [0; 32];
+        let mut writer = MessageWriter::new(&mut buf);
+        writer.push_typed(&uri).unwrap();
+        assert_eq!(writer.finish(), Ok(&message[..]));
+    }
+
+    #[test]
+    fn test_text_record() {
+        let payload = [0x02, b'e', b'n', b'H', b'i'];
+        let text = TextRecord::parse(&payload).unwrap();
+        assert_eq!(text, TextRecord::utf8("en", "Hi"));
+
+        let mut utf16 = [0; 8];
+        let text = TextRecord {
+            language: "de",
+            text: Text::utf16_from_str("Grüß", &mut utf16).unwrap(),
+        };
+        let mut buf = [0; 32];
+        let mut writer = MessageWriter::new(&mut buf);
+        writer.push_typed(&text).unwrap();
+        let message = writer.finish().unwrap();
+        assert_eq!(message[..7], [0xD1, 0x01, 0x0B, b'T', 0x82, b'd', b'e']);
+        let record = Records::new(message).next().unwrap().unwrap();
+        let RecordContent::Text(decoded) = record.content().unwrap() else {
+            panic!("expected a text record");
+        };
+        assert_eq!(decoded, text);
+        assert_eq!(decoded.text.to_string(), "Grüß");
+        // little endian with byte order mark
+        let little_endian = Text::Utf16(&[0xFF, 0xFE, b'o', 0x00, b'k', 0x00]);
+        assert_eq!(little_endian.to_string(), "ok");
+        let odd = Text::Utf16(&[0x00, b'o', 0x00]);
+        assert_eq!(odd.to_string(), "o\u{FFFD}");
+        assert_eq!(
+            TextRecord::parse(&[0x82, b'e', b'n', 0x00, b'o', 0x00]),
+            Err(NdefError::InvalidPayload)
+        );
+        assert_eq!(
+            TextRecord::parse(&[0x00, b'H', b'i']),
+            Err(NdefError::InvalidPayload)
+        );
+
+        let language = core::str::from_utf8(&[b'x'; 64]).unwrap();
+        let mut writer = MessageWriter::new(&mut buf);
+        assert_eq!(
+            writer.push_typed(&TextRecord::utf8(language, "Hi")),
+            Err(NdefError::InvalidPayload)
+        );
+        assert_eq!(
+            writer.push_typed(&TextRecord::utf8("dé", "Hi")),
+            Err(NdefError::InvalidPayload)
+        );
+        let odd = TextRecord {
+            language: "en",
+            text: Text::Utf16(&[0x00, b'o', 0x00]),
+        };
+        assert_eq!(writer.push_typed(&odd), Err(NdefError::InvalidPayload));
+        assert_eq!(writer.finish(), Ok(&[0xD0, 0x00, 0x00][..]));
+    }
+
+    #[test]
+    fn test_message() {
+        let payload = [0xAB; 300];
+        let mut buf = [0; 400];
+        let mut writer = MessageWriter::new(&mut buf);
+        let mime = MimeRecord {
+            media_type: "application/octet-stream",
+            data: &payload,
+        };
+        writer.push_typed_with_id(&mime, Some(b"#1")).unwrap();
+        let mut chunk = Record::new(Tnf::External, b"example.com:t", &[1, 2]);
+        chunk.chunked = true;
+        writer.push(&chunk).unwrap();
+        writer
+            .push(&Record::new(Tnf::Unchanged, &[], &[3]))
+            .unwrap();
+        let message = writer.finish().unwrap();
+        // long record with ID
+        assert_eq!(message[0], MB | IL | Tnf::MimeMedia as u8);
+        assert_eq!(message[2..6], 300u32.to_be_bytes());
+
+        let records: Vec<_> = Records::new(message).map(Result::unwrap).collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].id, Some(&b"#1"[..]));
+        assert_eq!(records[0].content(), Ok(RecordContent::Mime(mime)));
+        assert!(records[1].chunked && !records[1].message_begin);
+        assert_eq!(
+            records[1].content(),
+            Ok(RecordContent::External(ExternalRecord {
+                record_type: "example.com:t",
+                data: &[1, 2],
+            }))
+        );
+        assert!(records[2].message_end && !records[2].chunked);
+        assert_eq!(records[2].encoded_len(), 4);
+
+        let truncated = &message[..message.len() - 1];
+        assert_eq!(
+            Records::new(truncated).last(),
+            Some(Err(NdefError::Truncated))
+        );
+        assert_eq!(
+            Record::parse(&[0xD0, 0x01, 0x00, b'T']),
+            Err(NdefError::InvalidRecord)
+        );
+        let mut small = [0; 8];
+        let mut writer = MessageWriter::new(&mut small);
+        assert_eq!(writer.push_typed(&mime), Err(NdefError::BufTooSmall));
+        assert_eq!(writer.finish(), Ok(&[0xD0, 0x00, 0x00][..]));
+    }
+
+    #[test]
+    fn test_invalid_record() {
+        let mut buf = [0; 32];
+        let mut writer = MessageWriter::new(&mut buf);
+        let empty = Record::new(Tnf::Empty, b"T", &[]);
+        assert_eq!(writer.push(&empty), Err(NdefError::InvalidRecord));
+        assert_eq!(
+            writer.push(&Record::new(Tnf::Empty, &[], &[1])),
+            Err(NdefError::InvalidRecord)
+        );
+        let unchanged = Record::new(Tnf::Unchanged, &[], &[1]);
+        assert_eq!(writer.push(&unchanged), Err(NdefError::InvalidRecord));
+        let mut chunk = Record::new(Tnf::MimeMedia, b"a/b", &[1]);
+        chunk.chunked = true;
+        writer.push(&chunk).unwrap();
+        // a chunk is continued by an unchanged record only
+        assert_eq!(writer.push(&chunk), Err(NdefError::InvalidRecord));
+        let mut encoded = [0; 16];
+        let mut last_chunk = chunk;
+        last_chunk.message_end = true;
+        assert_eq!(
+            last_chunk.encode(&mut encoded),
+            Err(NdefError::InvalidRecord)
+        );
+        assert_eq!(writer.finish(), Err(NdefError::InvalidRecord));
+
+        // unchanged record without chunk before it
+        let message = [0x91, 0x01, 0x00, b'T', 0x56, 0x00, 0x00];
+        assert_eq!(
+            Records::new(&message).nth(1),
+            Some(Err(NdefError::InvalidRecord))
+        );
+        // chunk without continuation
+        let message = [0xB1, 0x01, 0x00, b'T', 0x51, 0x01, 0x00, b'T'];
+        assert_eq!(
+            Records::new(&message).nth(1),
+            Some(Err(NdefError::InvalidRecord))
+        );
+    }
+
+    #[test]
+    fn test_smart_poster() {
+        let mut nested = [0; 64];
+        let mut writer = MessageWriter::new(&mut nested);
+        writer
+            .push_typed(&UriRecord::new("https://example.com"))
+            .unwrap();
+        writer
+            .push_typed(&TextRecord::utf8("en", "Example"))
+            .unwrap();
+        writer.push_typed(&Action::Save).unwrap();
+        let nested = writer.finish().unwrap();
+
+        let mut buf = [0; 64];
+        let mut writer = MessageWriter::new(&mut buf);
+        writer.push_typed(&SmartPoster { message: nested }).unwrap();
+        let message = writer.finish().unwrap();
+        let record = Records::new(message).next().unwrap().unwrap();
+        let RecordContent::SmartPoster(poster) = record.content().unwrap() else {
+            panic!("expected a smart poster");
+        };
+        assert_eq!(poster.uri().unwrap().to_string(), "https://example.com");
+        assert_eq!(
+            poster.titles().collect::<Vec<_>>(),
+            [TextRecord::utf8("en", "Example")]
+        );
+        assert_eq!(poster.action(), Some(Action::Save));
+
+        let text = [0xD1, 0x01, 0x03, b'T', 0x00, b'h', b'i'];
+        assert_eq!(SmartPoster::parse(&text), Err(NdefError::InvalidPayload));
+        // two URI records
+        let uris = [
+            0x91, 0x01, 0x02, b'U', 0x01, b'a', 0x51, 0x01, 0x02, b'U', 0x01, b'b',
+        ];
+        assert_eq!(SmartPoster::parse(&uris), Err(NdefError::InvalidPayload));
+    }
+
+    #[test]
+    fn test_message_tlv() {
+        let message = [0xD0, 0x00, 0x00];
+        let mut buf = [0; 8];
+        assert_eq!(encode_message_tlv(&message, &mut buf), Ok(6));
+        assert_eq!(buf[..6], [0x03, 0x03, 0xD0, 0x00, 0x00, 0xFE]);
+        // lock control TLV and NULL TLV before the message
+        let data = [
+            0x01, 0x03, 0xA0, 0x10, 0x44, 0x00, 0x03, 0x03, 0xD0, 0x00, 0x00, 0xFE,
+        ];
+        assert_eq!(find_message_tlv(&data), Ok(Some(&message[..])));
+        assert_eq!(find_message_tlv(&[0x00, 0xFE, 0x03]), Ok(None));
+        assert_eq!(
+            find_message_tlv(&[0x03, 0x05, 0xD0]),
+            Err(NdefError::Truncated)
+        );
+
+        let long = [0x55; 300];
+        let mut buf = [0; 305];
+        assert_eq!(encode_message_tlv(&long, &mut buf), Ok(305));
+        assert_eq!(buf[..4], [0x03, 0xFF, 0x01, 0x2C]);
+        assert_eq!(find_message_tlv(&buf), Ok(Some(&long[..])));
+    }
+}
@@ -0,0 +1,620 @@
+//! NTAG21x and MIFARE Ultralight commands
+//!
+//! [`Ntag`] sends the NTAG21x commands to the first listed target.
+//! READ, WRITE and COMPATIBILITY_WRITE are sent with InDataExchange,
+//! the other commands with InCommunicateThru.
+//! A NAK of the tag is returned as [`Error::Status`].
+//!
+//! ```
+//! # use pn532::doc_test_helper::get_pn532;
+//! use pn532::ntag::Ntag;
+//! use pn532::Request;
+//! use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+//!
+//! let mut pn532 = get_pn532();
+//! if let Ok(&[1, ..]) = pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 14, 50.ms()) {
+//!     let mut tag = Ntag::new(&mut pn532, 50.ms());
+//!     let version = tag.get_version().unwrap();
+//!     // FAST_READ is split into multiple commands that fit into the buffer
+//!     let mut user_memory = [0; 144];
+//!     tag.fast_read(4, &mut user_memory).unwrap();
+//! }
+//! ```
+//!
+//! [`Ntag::detect_product`] identifies the [`Product`], whose [`MemoryMap`]
+//! contains the page numbers of the user memory and the configuration.
+//!
+//! ```
+//! use pn532::ntag::Product;
+//!
+//! let map = Product::Ntag215.memory_map();
+//! assert_eq!(map.user_memory_size(), 504);
+//! assert_eq!(map.config.unwrap().pwd, 133);
+//! ```
+//!
+//! [`Ntag::protect`] configures the password protection, later sessions
+//! check the password acknowledge with [`Ntag::authenticate`].
+//!
+//! ```
+//! # use pn532::doc_test_helper::get_pn532;
+//! use pn532::ntag::{Ntag, PasswordProtection, Product};
+//! use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+//!
+//! let mut pn532 = get_pn532();
+//! let protection = PasswordProtection {
+//!     pwd: [0x12, 0x34, 0x56, 0x78],
+//!     pack: [0xAB, 0xCD],
+//!     auth0: Product::Ntag213.memory_map().first_user_page,
+//!     read_protected: false,
+//!     lock_config: false,
+//!     auth_limit: 3,
+//! };
+//! let mut tag = Ntag::new(&mut pn532, 50.ms());
+//! if tag.protect(Product::Ntag213, &protection).is_ok() {
+//!     // next session
+//!     tag.authenticate(&protection.pwd, &protection.pack).unwrap();
+//! }
+//! ```
+use crate::originality::PublicKey;
+use crate::protocol::{frame_size, Error};
+use crate::responses::{exact, ResponseError};
+use crate::{CountDown, ErrorCode, Interface, Pn532, Request};
+
+/// Number of pages of a FAST_READ response that fit into a normal information frame
+const MAX_FAST_READ_PAGES: usize = 62;
+
+/// Decoded GET_VERSION response
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct NtagVersion {
+    /// 0x04 for NXP Semiconductors
+    pub vendor_id: u8,
+    /// 0x04 for NTAG, 0x03 for MIFARE Ultralight
+    pub product_type: u8,
+    pub product_subtype: u8,
+    pub major_version: u8,
+    pub minor_version: u8,
+    /// Bits 7 to 1 encode the exponent `n` of the memory size `2^n`,
+    /// bit 0 is set if the size is between `2^n` and `2^(n+1)`
+    pub storage_size: u8,
+    /// 0x03 for ISO/IEC 14443-3
+    pub protocol_type: u8,
+}
+
+/// Decodes the 8 bytes of the GET_VERSION response, the first byte is a fixed header
+impl From<[u8; 8]> for NtagVersion {
+    fn from(bytes: [u8; 8]) -> Self {
+        NtagVersion {
+            vendor_id: bytes[1],
+            product_type: bytes[2],
+            product_subtype: bytes[3],
+            major_version: bytes[4],
+            minor_version: bytes[5],
+            storage_size: bytes[6],
+            protocol_type: bytes[7],
+        }
+    }
+}
+
+/// NTAG or MIFARE Ultralight product
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Product {
+    /// MIFARE Ultralight without GET_VERSION, 48 bytes user memory
+    Ultralight,
+    /// MIFARE Ultralight EV1 MF0UL11, 48 bytes user memory
+    Mf0ul11,
+    /// MIFARE Ultralight EV1 MF0UL21, 128 bytes user memory
+    Mf0ul21,
+    /// 48 bytes user memory
+    Ntag210,
+    /// 128 bytes user memory
+    Ntag212,
+    /// 144 bytes user memory
+    Ntag213,
+    /// 504 bytes user memory
+    Ntag215,
+    /// 888 bytes user memory
+    Ntag216,
+}
+
+impl Product {
+    pub const fn memory_map(self) -> MemoryMap {
+        let (pages, dynamic_lock) = match self {
+            Product::Ultralight => (16, false),
+            Product::Mf0ul11 | Product::Ntag210 => (20, false),
+            Product::Mf0ul21 | Product::Ntag212 => (41, true),
+            Product::Ntag213 => (45, true),
+            Product::Ntag215 => (135, true),
+            Product::Ntag216 => (231, true),
+        };
+        if let Product::Ultralight = self {
+            return MemoryMap {
+                pages,
+                first_user_page: 4,
+                last_user_page: pages - 1,
+                dynamic_lock_page: None,
+                config: None,
+            };
+        }
+        // the configuration pages are the last 4 pages
+        let cfg0 = pages - 4;
+        let dynamic_lock_page = if dynamic_lock { Some(cfg0 - 1) } else { None };
+        MemoryMap {
+            pages,
+            first_user_page: 4,
+            last_user_page: match dynamic_lock_page {
+                Some(page) => page - 1,
+                None => cfg0 - 1,
+            },
+            dynamic_lock_page,
+            config: Some(ConfigPages {
+                cfg0,
+                cfg1: cfg0 + 1,
+                pwd: cfg0 + 2,
+                pack: cfg0 + 3,
+            }),
+        }
+    }
+}
+
+/// Identifies the product by vendor, product type and storage size
+impl TryFrom<NtagVersion> for Product {
+    type Error = ();
+
+    fn try_from(version: NtagVersion) -> Result<Product, ()> {
+        match (
+            version.vendor_id,
+            version.product_type,
+            version.storage_size,
+        ) {
+            (0x04, 0x03, 0x0B) => Ok(Product::Mf0ul11),
+            (0x04, 0x03, 0x0E) => Ok(Product::Mf0ul21),
+            (0x04, 0x04, 0x0B) => Ok(Product::Ntag210),
+            (0x04, 0x04, 0x0E) => Ok(Product::Ntag212),
+            (0x04, 0x04, 0x0F) => Ok(Product::Ntag213),
+            (0x04, 0x04, 0x11) => Ok(Product::Ntag215),
+            (0x04, 0x04, 0x13) => Ok(Product::Ntag216),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Page numbers of the configuration, each page is followed by the next one
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ConfigPages {
+    /// MIRROR, AUTH0 in byte 3
+    pub cfg0: u8,
+    /// ACCESS in byte 0
+    pub cfg1: u8,
+    /// Password, always reads as zeros
+    pub pwd: u8,
+    /// Password acknowledge in bytes 0 and 1, always reads as zeros
+    pub pack: u8,
+}
+
+/// Memory layout of a [`Product`]
+///
+/// Pages 0 to 3 contain the UID, the static lock bytes and the capability container.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct MemoryMap {
+    /// Total number of pages
+    pub pages: u8,
+    pub first_user_page: u8,
+    pub last_user_page: u8,
+    /// Page with the dynamic lock bytes
+    pub dynamic_lock_page: Option<u8>,
+    /// Configuration pages, `None` for products without password protection
+    pub config: Option<ConfigPages>,
+}
+
+impl MemoryMap {
+    /// Size of the user memory in bytes
+    pub const fn user_memory_size(&self) -> usize {
+        (self.last_user_page - self.first_user_page + 1) as usize * 4
+    }
+}
+
+/// Password protection written by [`Ntag::protect`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PasswordProtection {
+    pub pwd: [u8; 4],
+    /// Password acknowledge returned by a successful PWD_AUTH
+    pub pack: [u8; 2],
+    /// First protected page, protection is disabled if it is larger than the last page
+    pub auth0: u8,
+    /// PROT: protect read access as well as write access
+    pub read_protected: bool,
+    /// CFGLCK: permanently lock the configuration pages except PWD and PACK
+    pub lock_config: bool,
+    /// AUTHLIM: disable PWD_AUTH after `2^auth_limit` failed attempts, 0 allows unlimited attempts
+    pub auth_limit: u8,
+}
+
+/// NTAG21x tag that was listed as target 1 by the [`Pn532`]
+#[derive(Debug)]
+pub struct Ntag<'p, I, T: CountDown, const N: usize> {
+    pn532: &'p mut Pn532<I, T, N>,
+    timeout: T::Time,
+}
+
+impl<'p, I: Interface, T: CountDown, const N: usize> Ntag<'p, I, T, N>
+where
+    T::Time: Clone,
+{
+    /// Sends the commands with `pn532`, each with the given `timeout`
+    pub fn new(pn532: &'p mut Pn532<I, T, N>, timeout: T::Time) -> Self {
+        Ntag { pn532, timeout }
+    }
+
+    pub fn get_version(&mut self) -> Result<NtagVersion, Error<I::Error>> {
+        let bytes = self.process_exact::<1, 8>(&Request::NTAG_GET_VERSION)?;
+        Ok(NtagVersion::from(bytes))
+    }
+
+    /// Identifies the tag with the 7 byte `uid` by its GET_VERSION response
+    ///
+    /// If the tag does not support GET_VERSION, it is activated again with InListPassiveTarget
+    /// and identified as [`Product::Ultralight`] if it answers READ.
+    /// Unknown products fail with [`ResponseError::InvalidValue`].
+    pub fn detect_product(&mut self, uid: &[u8; 7]) -> Result<Product, Error<I::Error>> {
+        match self.get_version() {
+            Ok(version) => Product::try_from(version)
+                .map_err(|()| Error::Response(ResponseError::InvalidValue)),
+            Err(Error::Status(_)) => {
+                let request = Request::inlist_iso_a_target_by_uid(uid);
+                match self.pn532.process_auto(&request, self.timeout.clone())? {
+                    [0, ..] | [] => return Err(Error::Status(ErrorCode::Timeout)),
+                    _ => {}
+                }
+                self.read(0)?;
+                Ok(Product::Ultralight)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Reads 4 pages starting at `page`
+    pub fn read(&mut self, page: u8) -> Result<[u8; 16], Error<I::Error>> {
+        self.process_exact::<3, 16>(&Request::ntag_read(page))
+    }
+
+    /// Reads `buf.len() / 4` pages starting at `start_page`
+    ///
+    /// The range is split into as many FAST_READ commands as needed to fit into the buffer of the [`Pn532`].
+    ///
+    /// Fails with [`Error::InvalidArgument`] if the length of `buf` is not a multiple of 4
+    /// or if the pages would extend beyond page 255.
+    pub fn fast_read(&mut self, start_page: u8, buf: &mut [u8]) -> Result<(), Error<I::Error>> {
+        #[allow(clippy::manual_is_multiple_of)] // `is_multiple_of` needs Rust 1.87
+        if buf.len() % 4 != 0 {
+            // FAST_READ reads whole pages
+            return Err(Error::InvalidArgument);
+        }
+        let max_pages = (N.saturating_sub(frame_size(1)) / 4).min(MAX_FAST_READ_PAGES);
+        if max_pages == 0 {
+            return Err(Error::BufTooSmall);
+        }
+        let mut page = start_page;
+        for chunk in buf.chunks_mut(4 * max_pages) {
+            let pages = (chunk.len() / 4) as u8;
+            let end_page = page.checked_add(pages - 1).ok_or(Error::InvalidArgument)?;
+            let response = self.pn532.process_status(
+                &Request::ntag_fast_read(page, end_page),
+                chunk.len(),
+                self.timeout.clone(),
+            )?;
+            match response.data.len() {
+                len if len < chunk.len() => return Err(Error::Response(ResponseError::TooShort)),
+                len if len > chunk.len() => return Err(Error::Response(ResponseError::TooLong)),
+                _ => {}
+            }
+            chunk.copy_from_slice(response.data);
+            page = end_page.wrapping_add(1);
+        }
+        Ok(())
+    }
+
+    /// Writes the 4 bytes of `page`
+    pub fn write(&mut self, page: u8, data: &[u8; 4]) -> Result<(), Error<I::Error>> {
+        self.process_exact::<7, 0>(&Request::ntag_write(page, data))
+            .map(|_| ())
+    }
+
+    /// Writes the first 4 of the 16 `bytes` to `page` with the MIFARE Classic compatible write command
+    pub fn comp_write(&mut self, page: u8, data: &[u8; 16]) -> Result<(), Error<I::Error>> {
+        self.process_exact::<19, 0>(&Request::ntag_comp_write(page, data))
+            .map(|_| ())
+    }
+
+    /// Reads the 24 bit NFC counter
+    pub fn read_counter(&mut self) -> Result<u32, Error<I::Error>> {
+        let [b0, b1, b2] = self.process_exact::<2, 3>(&Request::ntag_read_cnt(0x02))?;
+        Ok(u32::from_le_bytes([b0, b1, b2, 0]))
+    }
+
+    /// Reads the 32 byte originality signature
+    pub fn read_signature(&mut self) -> Result<[u8; 32], Error<I::Error>> {
+        self.process_exact::<2, 32>(&Request::NTAG_READ_SIG)
+    }
+
+    /// Reads the originality signature and verifies it over the 7 byte `uid` with `key`,
+    /// usually [`PublicKey::NXP`]
+    pub fn verify_originality(
+        &mut self,
+        uid: &[u8; 7],
+        key: &PublicKey,
+    ) -> Result<bool, Error<I::Error>> {
+        let signature = self.read_signature()?;
+        Ok(key.verify(uid, &signature))
+    }
+
+    /// Authenticates with the password `pwd` and returns the password acknowledge PACK
+    pub fn pwd_auth(&mut self, pwd: &[u8; 4]) -> Result<[u8; 2], Error<I::Error>> {
+        self.process_exact::<5, 2>(&Request::ntag_pwd_auth(pwd))
+    }
+
+    /// Authenticates with the password `pwd`
+    ///
+    /// Fails with [`ResponseError::InvalidValue`] if the tag does not return the expected `pack`.
+    pub fn authenticate(&mut self, pwd: &[u8; 4], pack: &[u8; 2]) -> Result<(), Error<I::Error>> {
+        if self.pwd_auth(pwd)? != *pack {
+            return Err(Error::Response(ResponseError::InvalidValue));
+        }
+        Ok(())
+    }
+
+    /// Writes PWD and PACK and enables the password `protection` in the configuration pages of `product`
+    ///
+    /// If the tag is already protected, it must be authenticated first.
+    /// The other bits of the configuration, e.g. the NFC counter, are kept.
+    /// With [`lock_config`](PasswordProtection::lock_config), the tag is authenticated with the new password
+    /// before CFGLCK is set.
+    ///
+    /// Fails with [`Error::InvalidArgument`] before anything is written if `product` has no
+    /// configuration pages, e.g. [`Product::Ultralight`], or if `auth_limit` is larger than 7.
+    pub fn protect(
+        &mut self,
+        product: Product,
+        protection: &PasswordProtection,
+    ) -> Result<(), Error<I::Error>> {
+        const PROT: u8 = 0x80;
+        const CFGLCK: u8 = 0x40;
+        const AUTHLIM: u8 = 0x07;
+        let Some(config) = product.memory_map().config else {
+            return Err(Error::InvalidArgument);
+        };
+        if protection.auth_limit > AUTHLIM {
+            // AUTHLIM has 3 bits
+            return Err(Error::InvalidArgument);
+        }
+
+        let pages = self.read(config.cfg0)?;
+        let mut cfg0: [u8; 4] = pages[..4].try_into().unwrap();
+        let mut cfg1: [u8; 4] = pages[4..8].try_into().unwrap();
+        self.write(config.pwd, &protection.pwd)?;
+        let [pack0, pack1] = protection.pack;
+        self.write(config.pack, &[pack0, pack1, 0x00, 0x00])?;
+        cfg1[0] &= !(PROT | CFGLCK | AUTHLIM);
+        cfg1[0] |= protection.auth_limit;
+        if protection.read_protected {
+            cfg1[0] |= PROT;
+        }
+        self.write(config.cfg1, &cfg1)?;
+        cfg0[3] = protection.auth0;
+        self.write(config.cfg0, &cfg0)?;
+        if protection.lock_config {
+            self.authenticate(&protection.pwd, &protection.pack)?;
+            cfg1[0] |= CFGLCK;
+            self.write(config.cfg1, &cfg1)?;
+        }
+        Ok(())
+    }
+
+    /// Processes `request` and returns exactly `L` bytes of response data
+    fn process_exact<const M: usize, const L: usize>(
+        &mut self,
+        request: &Request<M>,
+    ) -> Result<[u8; L], Error<I::Error>> {
+        let response = self
+            .pn532
+            .process_status(request, L, self.timeout.clone())?;
+        exact(response.data).map_err(Error::Response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc_test_helper::NoOpTimer;
+    use crate::originality::tests::{KEY, SIGNATURE};
+    use crate::requests::CardType;
+    use crate::simulator::tags::{Ntag21x, NtagType};
+    use crate::simulator::{SimulatedPn532, VirtualTarget};
+    use core::cell::RefCell;
+    use core::convert::Infallible;
+    use core::time::Duration;
+    use std::rc::Rc;
+
+    const UID: [u8; 7] = [0x04, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+
+    #[test]
+    fn test_ntag() {
+        let mut ntag = Ntag21x::new(NtagType::Ntag216, UID);
+        ntag.signature = SIGNATURE;
+        ntag.set_password([1, 2, 3, 4], [0xAB, 0xCD]);
+        let ntag = Rc::new(RefCell::new(ntag));
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(ntag.clone());
+        let mut pn532: Pn532<_, _, 64> = Pn532::new(simulator, NoOpTimer);
+        let response = pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 14, Duration::ZERO);
+        assert_eq!(response.unwrap()[0], 1);
+
+        let mut tag = Ntag::new(&mut pn532, Duration::ZERO);
+        let version = tag.get_version().unwrap();
+        assert_eq!(version.product_type, 0x04);
+        assert_eq!(version.storage_size, 0x13);
+
+        for page in 4..226 {
+            tag.write(page, &[page; 4]).unwrap();
+        }
+        tag.comp_write(5, &[0x55; 16]).unwrap();
+        let mut memory = [0; 4 * 226];
+        // 13 pages per FAST_READ with a 64 byte buffer
+        tag.fast_read(0, &mut memory).unwrap();
+        let pages = ntag.borrow().pages()[..226].concat();
+        assert_eq!(memory[..], pages[..]);
+        assert_eq!(memory[20..24], [0x55; 4]);
+        assert_eq!(
+            tag.fast_read(0, &mut memory[..6]),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(
+            tag.fast_read(255, &mut memory[..8]),
+            Err(Error::InvalidArgument)
+        );
+        assert_eq!(
+            tag.read(5).unwrap()[..8],
+            [0x55, 0x55, 0x55, 0x55, 6, 6, 6, 6]
+        );
+
+        assert_eq!(tag.read_signature(), Ok(SIGNATURE));
+        let key = PublicKey::try_from(&KEY).unwrap();
+        assert_eq!(tag.verify_originality(&UID, &key), Ok(true));
+        assert_eq!(tag.verify_originality(&UID, &PublicKey::NXP), Ok(false));
+        assert_eq!(tag.pwd_auth(&[1, 2, 3, 4]), Ok([0xAB, 0xCD]));
+        // the NFC counter is disabled
+        assert_eq!(
+            tag.read_counter(),
+            Err(Error::Status(ErrorCode::RfProtocolError))
+        );
+    }
+
+    #[test]
+    fn test_memory_map() {
+        let sizes = [
+            (Product::Ultralight, 48),
+            (Product::Mf0ul11, 48),
+            (Product::Mf0ul21, 128),
+            (Product::Ntag210, 48),
+            (Product::Ntag212, 128),
+            (Product::Ntag213, 144),
+            (Product::Ntag215, 504),
+            (Product::Ntag216, 888),
+        ];
+        for (product, size) in sizes {
+            assert_eq!(product.memory_map().user_memory_size(), size);
+        }
+        let map = Product::Mf0ul21.memory_map();
+        assert_eq!(map.dynamic_lock_page, Some(0x24));
+        assert_eq!(map.config.unwrap().cfg0, 0x25);
+        let map = Product::Ntag210.memory_map();
+        assert_eq!(map.dynamic_lock_page, None);
+        assert_eq!(map.config.unwrap().pack, 19);
+    }
+
+    /// MIFARE Ultralight that does not know GET_VERSION
+    struct Ultralight {
+        halted: bool,
+    }
+
+    impl VirtualTarget for Ultralight {
+        fn card_type(&self) -> CardType {
+            CardType::IsoTypeA
+        }
+        fn target_data(&self) -> Vec<u8> {
+            [&[0x00, 0x44, 0x00, 0x07][..], &UID].concat()
+        }
+        fn activate(&mut self) -> bool {
+            self.halted = false;
+            true
+        }
+        fn exchange(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+            match data {
+                [0x30, _] if !self.halted => Ok(vec![0; 16]),
+                _ => {
+                    self.halted = true;
+                    Err(ErrorCode::RfProtocolError)
+                }
+            }
+        }
+    }
+
+    fn detect_product(target: impl VirtualTarget + 'static) -> Result<Product, Error<Infallible>> {
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(target);
+        let mut pn532: Pn532<_, _, 32> = Pn532::new(simulator, NoOpTimer);
+        pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 14, Duration::ZERO)?;
+        Ntag::new(&mut pn532, Duration::ZERO).detect_product(&UID)
+    }
+
+    #[test]
+    fn test_detect_product() {
+        let ntag = Ntag21x::new(NtagType::Ntag215, UID);
+        assert_eq!(detect_product(ntag), Ok(Product::Ntag215));
+        let ultralight = Ultralight { halted: false };
+        assert_eq!(detect_product(ultralight), Ok(Product::Ultralight));
+    }
+
+    #[test]
+    fn test_protect() {
+        let ntag = Rc::new(RefCell::new(Ntag21x::new(NtagType::Ntag213, UID)));
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(ntag.clone());
+        let mut pn532: Pn532<_, _, 32> = Pn532::new(simulator, NoOpTimer);
+        let list = |pn532: &mut Pn532<_, _, 32>| {
+            pn532
+                .process(&Request::INLIST_ONE_ISO_A_TARGET, 14, Duration::ZERO)
+                .unwrap();
+        };
+        list(&mut pn532);
+
+        let protection = PasswordProtection {
+            pwd: [1, 2, 3, 4],
+            pack: [0xAB, 0xCD],
+            auth0: 8,
+            read_protected: true,
+            lock_config: true,
+            auth_limit: 1,
+        };
+        let mut tag = Ntag::new(&mut pn532, Duration::ZERO);
+        assert_eq!(
+            tag.protect(Product::Ultralight, &protection),
+            Err(Error::InvalidArgument)
+        );
+        let too_many_attempts = PasswordProtection {
+            auth_limit: 8,
+            ..protection
+        };
+        assert_eq!(
+            tag.protect(Product::Ntag213, &too_many_attempts),
+            Err(Error::InvalidArgument)
+        );
+        tag.protect(Product::Ntag213, &protection).unwrap();
+        assert_eq!(
+            ntag.borrow().pages()[41..45],
+            [
+                [0x04, 0x00, 0x00, 8],
+                [0xC1, 0x05, 0x00, 0x00],
+                [1, 2, 3, 4],
+                [0xAB, 0xCD, 0x00, 0x00],
+            ]
+        );
+
+        list(&mut pn532);
+        let mut tag = Ntag::new(&mut pn532, Duration::ZERO);
+        assert!(tag.read(4).is_ok());
+        assert_eq!(tag.read(8), Err(Error::Status(ErrorCode::RfProtocolError)));
+        list(&mut pn532);
+        let mut tag = Ntag::new(&mut pn532, Duration::ZERO);
+        assert_eq!(
+            tag.authenticate(&[1, 2, 3, 4], &[0xAB, 0xCE]),
+            Err(Error::Response(ResponseError::InvalidValue))
+        );
+        tag.authenticate(&[1, 2, 3, 4], &[0xAB, 0xCD]).unwrap();
+        assert!(tag.read(8).is_ok());
+        // CFGLCK
+        assert_eq!(
+            tag.write(41, &[0x04, 0x00, 0x00, 0xFF]),
+            Err(Error::Status(ErrorCode::RfProtocolError))
+        );
+    }
+}
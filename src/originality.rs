@@ -0,0 +1,295 @@
+//! Verification of NXP originality signatures
+//!
+//! NTAG21x and MIFARE Ultralight EV1 tags return an ECDSA signature over their UID with READ_SIG,
+//! created with the secp128r1 curve and the private key of NXP.
+//! The UID is signed without hashing.
+//! [`Ntag::verify_originality`](crate::ntag::Ntag::verify_originality) reads and verifies the signature,
+//! custom keys are decoded with `PublicKey::try_from`.
+//!
+//! ```
+//! use pn532::originality::PublicKey;
+//!
+//! let uid = [0x04, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+//! let signature = [0; 32]; // returned by READ_SIG
+//! assert!(!PublicKey::NXP.verify(&uid, &signature));
+//! ```
+//!
+//! The arithmetic is implemented without allocations for `no_std`, but it is not constant time,
+//! which is fine since only public data is processed.
+
+/// Prime of the field
+const P: u128 = 0xFFFFFFFD_FFFFFFFF_FFFFFFFF_FFFFFFFF;
+/// Coefficient `b` of the curve `y^2 = x^3 - 3x + b`
+const B: u128 = 0xE87579C1_1079F43D_D824993C_2CEE5ED3;
+/// Order of the base point
+const N: u128 = 0xFFFFFFFE_00000000_75A30D1B_9038A115;
+const G: Point = Point {
+    x: 0x161FF752_8B899B2D_0C28607C_A52C5B86,
+    y: 0xCF5AC839_5BAFEB13_C02DA292_DDED7A83,
+    z: 1,
+};
+
+/// secp128r1 public key
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PublicKey {
+    x: u128,
+    y: u128,
+}
+
+impl PublicKey {
+    /// Originality key of NTAG21x and MIFARE Ultralight EV1
+    pub const NXP: PublicKey = PublicKey::from_uncompressed_unchecked(&[
+        0x04, 0x49, 0x4E, 0x1A, 0x38, 0x6D, 0x3D, 0x3C, 0xFE, 0x3D, 0xC1, 0x0E, 0x5D, 0xE6, 0x8A,
+        0x49, 0x9B, 0x1C, 0x20, 0x2D, 0xB5, 0xB1, 0x32, 0x39, 0x3E, 0x89, 0xED, 0x19, 0xFE, 0x5B,
+        0xE8, 0xBC, 0x61,
+    ]);
+
+    const fn from_uncompressed_unchecked(bytes: &[u8; 33]) -> Self {
+        PublicKey {
+            x: be_u128(bytes, 1),
+            y: be_u128(bytes, 17),
+        }
+    }
+
+    /// Verifies the 32 byte `signature`, consisting of `r` and `s` in big endian, over `message`
+    ///
+    /// Messages longer than 16 bytes are truncated to the size of the curve order.
+    pub fn verify(&self, message: &[u8], signature: &[u8; 32]) -> bool {
+        let r = be_u128(signature, 0);
+        let s = be_u128(signature, 16);
+        if r == 0 || r >= N || s == 0 || s >= N {
+            return false;
+        }
+        let mut e: u128 = 0;
+        for &byte in message.iter().take(16) {
+            e = e << 8 | byte as u128;
+        }
+        if e >= N {
+            e -= N;
+        }
+        let w = inv_mod(s, N);
+        let u1 = mul_mod(e, w, N);
+        let u2 = mul_mod(r, w, N);
+        let q = Point {
+            x: self.x,
+            y: self.y,
+            z: 1,
+        };
+        match G.mul(u1).add(&q.mul(u2)).to_affine() {
+            Some((x, _)) => (if x >= N { x - N } else { x }) == r,
+            None => false,
+        }
+    }
+}
+
+/// Decodes an uncompressed key `04 || x || y`, fails if the point is not on the curve
+impl TryFrom<&[u8; 33]> for PublicKey {
+    type Error = ();
+
+    fn try_from(bytes: &[u8; 33]) -> Result<PublicKey, ()> {
+        let key = PublicKey::from_uncompressed_unchecked(bytes);
+        if bytes[0] != 0x04 || key.x >= P || key.y >= P {
+            return Err(());
+        }
+        // y^2 = x^3 - 3x + b
+        let x = key.x;
+        let rhs = add_mod(
+            sub_mod(mul_mod(mul_mod(x, x, P), x, P), mul_mod(3, x, P), P),
+            B,
+            P,
+        );
+        if mul_mod(key.y, key.y, P) != rhs {
+            return Err(());
+        }
+        Ok(key)
+    }
+}
+
+const fn be_u128(bytes: &[u8], offset: usize) -> u128 {
+    let mut value = 0;
+    let mut i = 0;
+    while i < 16 {
+        value = value << 8 | bytes[offset + i] as u128;
+        i += 1;
+    }
+    value
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (sum, overflow) = a.overflowing_add(b);
+    if overflow || sum >= m {
+        sum.wrapping_sub(m)
+    } else {
+        sum
+    }
+}
+
+fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= b {
+        a - b
+    } else {
+        m - (b - a)
+    }
+}
+
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    let mut result = 0;
+    for i in (0..128).rev() {
+        result = add_mod(result, result, m);
+        if b >> i & 1 != 0 {
+            result = add_mod(result, a, m);
+        }
+    }
+    result
+}
+
+/// Inverse of `a` modulo the prime `m`
+fn inv_mod(a: u128, m: u128) -> u128 {
+    let mut result = 1;
+    let exponent = m - 2;
+    for i in (0..128).rev() {
+        result = mul_mod(result, result, m);
+        if exponent >> i & 1 != 0 {
+            result = mul_mod(result, a, m);
+        }
+    }
+    result
+}
+
+/// Point in Jacobian coordinates `(x / z^2, y / z^3)`, `z == 0` is the point at infinity
+#[derive(Copy, Clone, Debug)]
+struct Point {
+    x: u128,
+    y: u128,
+    z: u128,
+}
+
+impl Point {
+    const INFINITY: Point = Point { x: 1, y: 1, z: 0 };
+
+    fn double(&self) -> Point {
+        if self.z == 0 || self.y == 0 {
+            return Point::INFINITY;
+        }
+        let mul = |a, b| mul_mod(a, b, P);
+        let add = |a, b| add_mod(a, b, P);
+        let sub = |a, b| sub_mod(a, b, P);
+        // a = -3
+        let delta = mul(self.z, self.z);
+        let gamma = mul(self.y, self.y);
+        let beta = mul(self.x, gamma);
+        let alpha = mul(3, mul(sub(self.x, delta), add(self.x, delta)));
+        let x = sub(mul(alpha, alpha), mul(8, beta));
+        let z = sub(
+            sub(mul(add(self.y, self.z), add(self.y, self.z)), gamma),
+            delta,
+        );
+        let y = sub(mul(alpha, sub(mul(4, beta), x)), mul(8, mul(gamma, gamma)));
+        Point { x, y, z }
+    }
+
+    fn add(&self, other: &Point) -> Point {
+        if self.z == 0 {
+            return *other;
+        }
+        if other.z == 0 {
+            return *self;
+        }
+        let mul = |a, b| mul_mod(a, b, P);
+        let sub = |a, b| sub_mod(a, b, P);
+        let z1z1 = mul(self.z, self.z);
+        let z2z2 = mul(other.z, other.z);
+        let u1 = mul(self.x, z2z2);
+        let u2 = mul(other.x, z1z1);
+        let s1 = mul(self.y, mul(other.z, z2z2));
+        let s2 = mul(other.y, mul(self.z, z1z1));
+        let h = sub(u2, u1);
+        let r = sub(s2, s1);
+        if h == 0 {
+            return if r == 0 {
+                self.double()
+            } else {
+                Point::INFINITY
+            };
+        }
+        let hh = mul(h, h);
+        let hhh = mul(h, hh);
+        let v = mul(u1, hh);
+        let x = sub(sub(mul(r, r), hhh), mul(2, v));
+        let y = sub(mul(r, sub(v, x)), mul(s1, hhh));
+        let z = mul(mul(self.z, other.z), h);
+        Point { x, y, z }
+    }
+
+    fn mul(&self, scalar: u128) -> Point {
+        let mut result = Point::INFINITY;
+        for i in (0..128).rev() {
+            result = result.double();
+            if scalar >> i & 1 != 0 {
+                result = result.add(self);
+            }
+        }
+        result
+    }
+
+    fn to_affine(self) -> Option<(u128, u128)> {
+        if self.z == 0 {
+            return None;
+        }
+        let z_inv = inv_mod(self.z, P);
+        let z_inv2 = mul_mod(z_inv, z_inv, P);
+        let x = mul_mod(self.x, z_inv2, P);
+        let y = mul_mod(self.y, mul_mod(z_inv2, z_inv, P), P);
+        Some((x, y))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub const UID: [u8; 7] = [0x04, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+    /// Key of the private key 0x0123456789ABCDEF0011223344556677
+    pub const KEY: [u8; 33] = [
+        0x04, 0x1B, 0x9D, 0x07, 0xA4, 0xA7, 0xB5, 0xEC, 0xE0, 0x86, 0x03, 0x2A, 0x4A, 0xFE, 0x23,
+        0x13, 0x36, 0xA2, 0x2D, 0x29, 0x0C, 0xBB, 0xAF, 0x36, 0xB8, 0x46, 0x8E, 0x9D, 0x4A, 0x55,
+        0xFC, 0x69, 0x48,
+    ];
+    pub const SIGNATURE: [u8; 32] = [
+        0xFF, 0xAE, 0xA8, 0xEE, 0xA4, 0xA6, 0x7C, 0xC4, 0x8E, 0x7C, 0xDC, 0x21, 0xEA, 0x82, 0x87,
+        0x44, 0x10, 0x9E, 0x0D, 0x88, 0x25, 0xE7, 0x34, 0x8A, 0xBB, 0x06, 0xF6, 0x03, 0x2F, 0x92,
+        0xE0, 0xE2,
+    ];
+
+    #[test]
+    fn test_curve() {
+        assert!(G.mul(N).to_affine().is_none());
+        let g = PublicKey::try_from(&[
+            0x04, 0x16, 0x1F, 0xF7, 0x52, 0x8B, 0x89, 0x9B, 0x2D, 0x0C, 0x28, 0x60, 0x7C, 0xA5,
+            0x2C, 0x5B, 0x86, 0xCF, 0x5A, 0xC8, 0x39, 0x5B, 0xAF, 0xEB, 0x13, 0xC0, 0x2D, 0xA2,
+            0x92, 0xDD, 0xED, 0x7A, 0x83,
+        ]);
+        assert_eq!(g, Ok(PublicKey { x: G.x, y: G.y }));
+        let nxp = PublicKey::NXP;
+        let (x, y) = (nxp.x.to_be_bytes(), nxp.y.to_be_bytes());
+        let bytes: [u8; 33] = [&[0x04][..], &x, &y].concat().try_into().unwrap();
+        assert_eq!(PublicKey::try_from(&bytes), Ok(nxp));
+        let mut invalid = bytes;
+        invalid[32] ^= 1;
+        assert_eq!(PublicKey::try_from(&invalid), Err(()));
+    }
+
+    #[test]
+    fn test_verify() {
+        let key = PublicKey::try_from(&KEY).unwrap();
+        assert!(key.verify(&UID, &SIGNATURE));
+        assert!(!PublicKey::NXP.verify(&UID, &SIGNATURE));
+        let mut uid = UID;
+        uid[6] ^= 1;
+        assert!(!key.verify(&uid, &SIGNATURE));
+        let mut signature = SIGNATURE;
+        signature[31] ^= 1;
+        assert!(!key.verify(&UID, &signature));
+        assert!(!key.verify(&UID, &[0; 32]));
+    }
+}
@@ -1,22 +1,42 @@
 use crate::{
-    requests::{BorrowedRequest, Command},
-    Interface,
+    requests::{BorrowedRequest, Command, IntoRequest},
+    responses::{Response, ResponseError, StatusResponse},
+    AsyncInterface, ErrorCode, Interface,
 };
 use core::{
     convert::Infallible,
     fmt::Debug,
-    future::Future,
-    pin::Pin,
-    task::{Context, Poll},
+    future::{poll_fn, Future},
+    ops::Range,
+    pin::pin,
+    task::Poll,
 };
 
 const PREAMBLE: [u8; 3] = [0x00, 0x00, 0xFF];
+const START_CODE: [u8; 2] = [0x00, 0xFF];
 const POSTAMBLE: u8 = 0x00;
-const ACK: [u8; 6] = [0x00, 0x00, 0xFF, 0x00, 0xFF, 0x00];
+pub(crate) const ACK: [u8; 6] = [0x00, 0x00, 0xFF, 0x00, 0xFF, 0x00];
+const NACK: [u8; 6] = [0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00];
+/// `LEN` and `LCS` of an extended information frame (see 6.2.1.3)
+const EXTENDED_FRAME: [u8; 2] = [0xFF, 0xFF];
+/// Largest `LEN` that is sent in a normal information frame
+const MAX_NORMAL_FRAME_LEN: usize = 0xFE;
 
 const HOST_TO_PN532: u8 = 0xD4;
 const PN532_TO_HOST: u8 = 0xD5;
 
+/// Number of bytes needed to frame `data_len` bytes of command data.
+///
+/// Normal information frames add 9 bytes, extended information frames add 12 bytes.
+pub(crate) const fn frame_size(data_len: usize) -> usize {
+    // frame identifier + command + data
+    if data_len > MAX_NORMAL_FRAME_LEN - 2 {
+        data_len.saturating_add(12)
+    } else {
+        data_len + 9
+    }
+}
+
 /// Pn532 Error
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Error<E: Debug> {
@@ -28,12 +48,24 @@ pub enum Error<E: Debug> {
     Syntax,
     /// CRC for either the length or the data is wrong
     CrcError,
-    /// The provided `response_len` was too low
+    /// The provided `response_len` was too low or does not fit into the internal buffer
     BufTooSmall,
+    /// The request does not fit into the internal buffer
+    RequestTooLarge,
+    /// An argument was rejected before anything was sent to the PN532
+    InvalidArgument,
     /// Did not receive an ACK frame in time
     TimeoutAck,
     /// Did not receive a response frame in time
     TimeoutResponse,
+    /// [`Transaction::poll`](crate::Transaction::poll) was called again after it returned an error
+    TransactionFailed,
+    /// Could not decode the response data
+    Response(ResponseError),
+    /// The status byte of the response contains this error code
+    Status(ErrorCode),
+    /// The status byte of the response contains an error code that is not described in the User Manual
+    UnknownStatus(u8),
     /// Interface specific Error
     InterfaceError(E),
 }
@@ -46,8 +78,12 @@ impl<E: Debug> From<E> for Error<E> {
 
 /// Main struct of this crate
 ///
-/// Provides blocking methods [`process`](Pn532::process) and [`process_async`](Pn532::process_async)
+/// Provides blocking method [`process`](Pn532::process) and async method [`process_async`](Pn532::process_async)
 /// for sending requests and parsing responses.
+/// [`process_typed`](Pn532::process_typed) additionally decodes the response into one of the types
+/// in [`responses`](crate::responses).
+///
+/// [`start`](Pn532::start) returns a [`Transaction`](crate::Transaction) that can be polled without blocking.
 ///
 /// Other methods can be used if fine-grain control is required.
 ///
@@ -55,12 +91,20 @@ impl<E: Debug> From<E> for Error<E> {
 /// The `Pn532` uses an internal buffer for sending and receiving messages.
 /// The size of the buffer is determined by the `N` type parameter which has a default value of `32`.
 ///
-/// Choosing `N` too small will result in **panics**.
+/// Sending a [`Request`](crate::requests::Request) reference that does not fit into the buffer
+/// fails to compile (see [`IntoRequest`]).
+/// A [`BorrowedRequest`] that does not fit returns [`Error::RequestTooLarge`]
+/// and a `response_len` that does not fit returns [`Error::BufTooSmall`].
 ///
 /// The following inequality should hold for all requests and responses:
 /// ```text
 /// N - 9 >= max(response_len, M)
 /// ```
+/// Requests and responses with more than 252 bytes of data are sent in extended information frames,
+/// which need 3 more bytes:
+/// ```text
+/// N - 12 >= max(response_len, M)
+/// ```
 /// where
 /// * `N` is the const generic type parameter of this struct.
 /// * `response_len` is the largest number passed to
@@ -70,7 +114,9 @@ impl<E: Debug> From<E> for Error<E> {
 pub struct Pn532<I, T, const N: usize = 32> {
     pub interface: I,
     pub timer: T,
-    buf: [u8; N],
+    pub(crate) buf: [u8; N],
+    retransmits: u8,
+    skipped: usize,
 }
 
 /// A count-down timer
@@ -126,6 +172,36 @@ pub trait CountDown {
     fn wait(&mut self) -> nb::Result<(), Infallible>;
 }
 
+/// An async delay, used as timer of the async methods
+///
+/// # Example implementation for the [embassy-time](https://crates.io/crates/embassy-time) crate
+/// ```
+/// # use pn532::doc_test_helper::embassy_time;
+/// use pn532::AsyncDelay;
+///
+/// struct EmbassyDelay;
+///
+/// impl AsyncDelay for EmbassyDelay {
+///     type Time = embassy_time::Duration;
+///
+///     async fn delay(&mut self, duration: Self::Time) {
+///         embassy_time::Timer::after(duration).await
+///     }
+/// }
+/// ```
+///
+/// # Contract
+///
+/// - `self.delay(time).await` MUST take AT LEAST the time specified by `time`.
+#[allow(async_fn_in_trait)]
+pub trait AsyncDelay {
+    /// The unit of time used by this delay
+    type Time;
+
+    /// Completes after `time` has passed
+    async fn delay(&mut self, time: Self::Time);
+}
+
 impl<I: Interface, T: CountDown, const N: usize> Pn532<I, T, N> {
     /// Send a request, wait for an ACK and then wait for a response.
     ///
@@ -142,12 +218,12 @@ impl<I: Interface, T: CountDown, const N: usize> Pn532<I, T, N> {
     #[inline]
     pub fn process<'a>(
         &mut self,
-        request: impl Into<BorrowedRequest<'a>>,
+        request: impl IntoRequest<'a, N>,
         response_len: usize,
         timeout: T::Time,
     ) -> Result<&[u8], Error<I::Error>> {
         // codegen trampoline: https://github.com/rust-lang/rust/issues/77960
-        self._process(request.into(), response_len, timeout)
+        self._process(request.into_request(), response_len, timeout)
     }
     fn _process(
         &mut self,
@@ -156,6 +232,50 @@ impl<I: Interface, T: CountDown, const N: usize> Pn532<I, T, N> {
         timeout: T::Time,
     ) -> Result<&[u8], Error<I::Error>> {
         let sent_command = request.command;
+        self.send_and_wait_for_response(request, timeout)?;
+        let range = self.receive_response_with_retransmits(sent_command, Some(response_len))?;
+        Ok(&self.buf[range])
+    }
+
+    /// Send a request, wait for an ACK and then wait for a response.
+    ///
+    /// Unlike [`process`](Pn532::process), the length of the response is read from the frame header,
+    /// so no `response_len` has to be provided.
+    /// See [`receive_response_auto`](Pn532::receive_response_auto) for details.
+    ///
+    /// ```
+    /// # use pn532::doc_test_helper::get_pn532;
+    /// use pn532::Request;
+    /// use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+    ///
+    /// let mut pn532 = get_pn532();
+    /// let result = pn532.process_auto(&Request::GET_FIRMWARE_VERSION, 50.ms());
+    /// ```
+    #[inline]
+    pub fn process_auto<'a>(
+        &mut self,
+        request: impl IntoRequest<'a, N>,
+        timeout: T::Time,
+    ) -> Result<&[u8], Error<I::Error>> {
+        // codegen trampoline: https://github.com/rust-lang/rust/issues/77960
+        self._process_auto(request.into_request(), timeout)
+    }
+    fn _process_auto(
+        &mut self,
+        request: BorrowedRequest<'_>,
+        timeout: T::Time,
+    ) -> Result<&[u8], Error<I::Error>> {
+        let sent_command = request.command;
+        self.send_and_wait_for_response(request, timeout)?;
+        let range = self.receive_response_with_retransmits(sent_command, None)?;
+        Ok(&self.buf[range])
+    }
+
+    fn send_and_wait_for_response(
+        &mut self,
+        request: BorrowedRequest<'_>,
+        timeout: T::Time,
+    ) -> Result<(), Error<I::Error>> {
         self.timer.start(timeout);
         self._send(request)?;
         while self.interface.wait_ready()?.is_pending() {
@@ -164,12 +284,97 @@ impl<I: Interface, T: CountDown, const N: usize> Pn532<I, T, N> {
             }
         }
         self.receive_ack()?;
+        self.wait_for_response()
+    }
+
+    fn wait_for_response(&mut self) -> Result<(), Error<I::Error>> {
         while self.interface.wait_ready()?.is_pending() {
             if self.timer.wait().is_ok() {
                 return Err(Error::TimeoutResponse);
             }
         }
-        self.receive_response(sent_command, response_len)
+        Ok(())
+    }
+
+    /// Receives the response and asks for retransmissions of corrupted response frames
+    fn receive_response_with_retransmits(
+        &mut self,
+        sent_command: Command,
+        response_len: Option<usize>,
+    ) -> Result<Range<usize>, Error<I::Error>> {
+        let mut retransmits = self.retransmits;
+        loop {
+            match self.receive_response_range(sent_command, response_len) {
+                Err(Error::CrcError | Error::BadResponseFrame) if retransmits > 0 => {
+                    retransmits -= 1;
+                    self.request_retransmit()?;
+                    self.wait_for_response()?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Send a request, wait for an ACK and then wait for a response and decode it into `R`.
+    ///
+    /// `response_len` is the largest expected length of the returned data.
+    ///
+    /// ```
+    /// # use pn532::doc_test_helper::get_pn532;
+    /// use pn532::responses::FirmwareVersion;
+    /// use pn532::Request;
+    /// use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+    ///
+    /// let mut pn532 = get_pn532();
+    /// if let Ok(version) = pn532.process_typed::<FirmwareVersion>(&Request::GET_FIRMWARE_VERSION, 4, 50.ms()) {
+    ///     println!("firmware version: {}.{}", version.version, version.revision);
+    /// }
+    /// ```
+    #[inline]
+    pub fn process_typed<'a, 'r, R: Response<'r>>(
+        &'r mut self,
+        request: impl IntoRequest<'a, N>,
+        response_len: usize,
+        timeout: T::Time,
+    ) -> Result<R, Error<I::Error>> {
+        let request = request.into_request();
+        if !R::is_response_to(request.command) {
+            return Err(Error::Response(ResponseError::UnexpectedCommand));
+        }
+        let data = self._process(request, response_len, timeout)?;
+        R::parse(data).map_err(response_error)
+    }
+
+    /// Send a request whose response starts with a status byte, wait for an ACK and then wait for a response.
+    ///
+    /// The status byte is stripped from the returned data.
+    /// An error code in the status byte is returned as [`Error::Status`] or [`Error::UnknownStatus`].
+    ///
+    /// `response_len` is the largest expected length of the returned data without the status byte.
+    ///
+    /// ```
+    /// # use pn532::doc_test_helper::get_pn532;
+    /// use pn532::{Error, ErrorCode, Request};
+    /// use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+    ///
+    /// let mut pn532 = get_pn532();
+    /// match pn532.process_status(&Request::ntag_read(4), 16, 50.ms()) {
+    ///     Ok(response) => {
+    ///         assert!(!response.more_information);
+    ///         println!("page 4: {:?}", response.data);
+    ///     }
+    ///     Err(Error::Status(ErrorCode::Timeout)) => println!("no tag"),
+    ///     Err(e) => println!("error: {:?}", e),
+    /// }
+    /// ```
+    #[inline]
+    pub fn process_status<'a>(
+        &mut self,
+        request: impl IntoRequest<'a, N>,
+        response_len: usize,
+        timeout: T::Time,
+    ) -> Result<StatusResponse<'_>, Error<I::Error>> {
+        self.process_typed(request, response_len.saturating_add(1), timeout)
     }
 
     /// Send a request and wait for an ACK.
@@ -185,11 +390,11 @@ impl<I: Interface, T: CountDown, const N: usize> Pn532<I, T, N> {
     #[inline]
     pub fn process_no_response<'a>(
         &mut self,
-        request: impl Into<BorrowedRequest<'a>>,
+        request: impl IntoRequest<'a, N>,
         timeout: T::Time,
     ) -> Result<(), Error<I::Error>> {
         // codegen trampoline: https://github.com/rust-lang/rust/issues/77960
-        self._process_no_response(request.into(), timeout)
+        self._process_no_response(request.into_request(), timeout)
     }
     fn _process_no_response(
         &mut self,
@@ -206,16 +411,37 @@ impl<I: Interface, T: CountDown, const N: usize> Pn532<I, T, N> {
         self.receive_ack()
     }
 }
-impl<I: Interface, T, const N: usize> Pn532<I, T, N> {
+impl<I, T, const N: usize> Pn532<I, T, N> {
     /// Create a Pn532 instance
     pub fn new(interface: I, timer: T) -> Self {
         Pn532 {
             interface,
             timer,
             buf: [0; N],
+            retransmits: 0,
+            skipped: 0,
         }
     }
 
+    /// Set how often [`process`](Pn532::process), [`process_typed`](Pn532::process_typed) and
+    /// [`process_auto`](Pn532::process_auto) ask the PN532 to retransmit a corrupted response frame
+    /// (see [`request_retransmit`](Pn532::request_retransmit)) before returning
+    /// [`Error::CrcError`] or [`Error::BadResponseFrame`].
+    ///
+    /// The default is `0`. Retransmissions have to complete within the timeout of the request.
+    pub fn set_retransmits(&mut self, retransmits: u8) {
+        self.retransmits = retransmits;
+    }
+
+    /// Number of bytes that were skipped in front of the last received response frame,
+    /// e.g. additional zeros or a stale ACK frame.
+    /// A single preamble byte in front of the start code is not counted.
+    pub fn skipped_bytes(&self) -> usize {
+        self.skipped
+    }
+}
+
+impl<I: Interface, T, const N: usize> Pn532<I, T, N> {
     /// Send a request.
     ///
     /// ```
@@ -226,40 +452,13 @@ impl<I: Interface, T, const N: usize> Pn532<I, T, N> {
     /// pn532.send(&Request::GET_FIRMWARE_VERSION);
     /// ```
     #[inline]
-    pub fn send<'a>(
-        &mut self,
-        request: impl Into<BorrowedRequest<'a>>,
-    ) -> Result<(), Error<I::Error>> {
+    pub fn send<'a>(&mut self, request: impl IntoRequest<'a, N>) -> Result<(), Error<I::Error>> {
         // codegen trampoline: https://github.com/rust-lang/rust/issues/77960
-        self._send(request.into())
+        self._send(request.into_request())
     }
     fn _send(&mut self, request: BorrowedRequest<'_>) -> Result<(), Error<I::Error>> {
-        let data_len = request.data.len();
-        let frame_len = 2 + data_len as u8; // frame identifier + command + data
-
-        let mut data_sum = HOST_TO_PN532.wrapping_add(request.command as u8); // sum(command + data + frame identifier)
-        for &byte in request.data {
-            data_sum = data_sum.wrapping_add(byte);
-        }
-
-        const fn to_checksum(sum: u8) -> u8 {
-            (!sum).wrapping_add(1)
-        }
-
-        self.buf[0] = PREAMBLE[0];
-        self.buf[1] = PREAMBLE[1];
-        self.buf[2] = PREAMBLE[2];
-        self.buf[3] = frame_len;
-        self.buf[4] = to_checksum(frame_len);
-        self.buf[5] = HOST_TO_PN532;
-        self.buf[6] = request.command as u8;
-
-        self.buf[7..7 + data_len].copy_from_slice(request.data);
-
-        self.buf[7 + data_len] = to_checksum(data_sum);
-        self.buf[8 + data_len] = POSTAMBLE;
-
-        self.interface.write(&mut self.buf[..9 + data_len])?;
+        let frame_len = write_frame(&mut self.buf, request)?;
+        self.interface.write(&mut self.buf[..frame_len])?;
         Ok(())
     }
 
@@ -293,6 +492,10 @@ impl<I: Interface, T, const N: usize> Pn532<I, T, N> {
     /// the interface was checked to be ready.
     ///
     /// `response_len` is the largest expected length of the returned data.
+    /// If the frame is preceded by garbage or a stale ACK frame, the rest of the frame is read
+    /// with [`Interface::continue_frame`].
+    /// Links that restart the frame with every read (I2C) cannot do this and return
+    /// [`Error::BadResponseFrame`] if the end of the frame was pushed out of the read bytes.
     ///
     /// ```
     /// # use pn532::doc_test_helper::get_pn532;
@@ -315,11 +518,97 @@ impl<I: Interface, T, const N: usize> Pn532<I, T, N> {
         sent_command: Command,
         response_len: usize,
     ) -> Result<&[u8], Error<I::Error>> {
-        let response_buf = &mut self.buf[..response_len + 9];
-        response_buf.fill(0); // zero out buf
-        self.interface.read(response_buf)?;
+        let range = self.receive_response_range(sent_command, Some(response_len))?;
+        Ok(&self.buf[range])
+    }
+
+    /// Reads `response_len` bytes of response data or the whole frame if `response_len` is `None`
+    pub(crate) fn receive_response_range(
+        &mut self,
+        sent_command: Command,
+        response_len: Option<usize>,
+    ) -> Result<Range<usize>, Error<I::Error>> {
+        let frame_len = match response_len {
+            Some(response_len) => {
+                let response_buf = self
+                    .buf
+                    .get_mut(..frame_size(response_len))
+                    .ok_or(Error::BufTooSmall)?;
+                response_buf.fill(0); // zero out buf
+                self.interface.read(response_buf)?;
+                let len = response_buf.len();
+                if frame_pushed_out(response_buf) {
+                    self.interface.continue_frame(&mut self.buf, len)?
+                } else {
+                    len
+                }
+            }
+            None => {
+                self.buf.fill(0); // zero out buf
+                self.interface.read_frame(&mut self.buf)?
+            }
+        };
+        self.skipped = skipped_bytes(&self.buf[..frame_len]);
         let expected_response_command = sent_command as u8 + 1;
-        parse_response(response_buf, expected_response_command)
+        parse_response(&self.buf[..frame_len], expected_response_command)
+    }
+
+    /// Receive a response frame without knowing its length up front.
+    /// This should be done after [`send`](Pn532::send) and [`receive_ack`](Pn532::receive_ack) was called and
+    /// the interface was checked to be ready.
+    ///
+    /// The frame is read with [`Interface::read_frame`].
+    /// SPI and HSU links first read the frame header and then exactly the remaining bytes of the frame.
+    /// Other links fill the whole internal buffer.
+    ///
+    /// ```
+    /// # use pn532::doc_test_helper::get_pn532;
+    /// use core::task::Poll;
+    /// use pn532::{Interface, Request};
+    ///
+    /// let mut pn532 = get_pn532();
+    /// pn532.send(&Request::GET_FIRMWARE_VERSION);
+    /// // do something else
+    /// if let Poll::Ready(Ok(_)) = pn532.interface.wait_ready() {
+    ///     pn532.receive_ack();
+    /// }
+    /// // do something else
+    /// if let Poll::Ready(Ok(_)) = pn532.interface.wait_ready() {
+    ///     let result = pn532.receive_response_auto(Request::GET_FIRMWARE_VERSION.command);
+    /// }
+    /// ```
+    pub fn receive_response_auto(
+        &mut self,
+        sent_command: Command,
+    ) -> Result<&[u8], Error<I::Error>> {
+        let range = self.receive_response_range(sent_command, None)?;
+        Ok(&self.buf[range])
+    }
+
+    /// Send a NACK frame to ask the PN532 to send the last response frame again.
+    ///
+    /// This is useful if the response frame was corrupted on the serial link,
+    /// since the command is not executed a second time.
+    /// Once the interface is ready, the response frame can be received again.
+    ///
+    /// ```
+    /// # use pn532::doc_test_helper::get_pn532;
+    /// use core::task::Poll;
+    /// use pn532::{Error, Interface, Request};
+    ///
+    /// let mut pn532 = get_pn532();
+    /// // send request and receive ACK
+    /// if let Err(Error::CrcError) = pn532.receive_response(Request::GET_FIRMWARE_VERSION.command, 4) {
+    ///     pn532.request_retransmit();
+    ///     if let Poll::Ready(Ok(_)) = pn532.interface.wait_ready() {
+    ///         let result = pn532.receive_response(Request::GET_FIRMWARE_VERSION.command, 4);
+    ///     }
+    /// }
+    /// ```
+    pub fn request_retransmit(&mut self) -> Result<(), Error<I::Error>> {
+        #[allow(const_item_mutation)]
+        self.interface.write(&mut NACK)?;
+        Ok(())
     }
 
     /// Send an ACK frame to force the PN532 to abort the current process.
@@ -333,13 +622,18 @@ impl<I: Interface, T, const N: usize> Pn532<I, T, N> {
     }
 }
 
-impl<I: Interface, const N: usize> Pn532<I, (), N> {
+impl<I: AsyncInterface, const N: usize> Pn532<I, (), N> {
     /// Create a Pn532 instance without a timer
+    ///
+    /// Without a timer, the async methods wait for the PN532 indefinitely.
+    /// Use [`new`](Pn532::new) with an [`AsyncDelay`] to get timeouts.
     pub fn new_async(interface: I) -> Self {
         Pn532 {
             interface,
             timer: (),
             buf: [0; N],
+            retransmits: 0,
+            skipped: 0,
         }
     }
 
@@ -357,11 +651,12 @@ impl<I: Interface, const N: usize> Pn532<I, (), N> {
     #[inline]
     pub async fn process_async<'a>(
         &mut self,
-        request: impl Into<BorrowedRequest<'a>>,
+        request: impl IntoRequest<'a, N>,
         response_len: usize,
     ) -> Result<&[u8], Error<I::Error>> {
         // codegen trampoline: https://github.com/rust-lang/rust/issues/77960
-        self._process_async(request.into(), response_len).await
+        self._process_async(request.into_request(), response_len)
+            .await
     }
     async fn _process_async(
         &mut self,
@@ -369,11 +664,12 @@ impl<I: Interface, const N: usize> Pn532<I, (), N> {
         response_len: usize,
     ) -> Result<&[u8], Error<I::Error>> {
         let sent_command = request.command;
-        self._send(request)?;
-        self.wait_ready_future().await?;
-        self.receive_ack()?;
-        self.wait_ready_future().await?;
-        self.receive_response(sent_command, response_len)
+        self.send_async(request).await?;
+        self.interface.wait_ready().await?;
+        self.receive_ack_async().await?;
+        self.interface.wait_ready().await?;
+        self.receive_response_async(sent_command, response_len)
+            .await
     }
 
     /// Send a request and wait for an ACK.
@@ -387,40 +683,411 @@ impl<I: Interface, const N: usize> Pn532<I, (), N> {
     #[inline]
     pub async fn process_no_response_async<'a>(
         &mut self,
-        request: impl Into<BorrowedRequest<'a>>,
+        request: impl IntoRequest<'a, N>,
     ) -> Result<(), Error<I::Error>> {
         // codegen trampoline: https://github.com/rust-lang/rust/issues/77960
-        self._process_no_response_async(request.into()).await
+        self._process_no_response_async(request.into_request())
+            .await
     }
     async fn _process_no_response_async(
         &mut self,
         request: BorrowedRequest<'_>,
     ) -> Result<(), Error<I::Error>> {
-        self._send(request)?;
-        self.wait_ready_future().await?;
-        self.receive_ack()?;
+        self.send_async(request).await?;
+        self.interface.wait_ready().await?;
+        self.receive_ack_async().await
+    }
+}
+
+impl<I: AsyncInterface, T, const N: usize> Pn532<I, T, N> {
+    async fn send_async(&mut self, request: BorrowedRequest<'_>) -> Result<(), Error<I::Error>> {
+        let frame_len = write_frame(&mut self.buf, request)?;
+        self.interface.write(&mut self.buf[..frame_len]).await?;
         Ok(())
     }
 
-    fn wait_ready_future(&mut self) -> WaitReadyFuture<I> {
-        WaitReadyFuture {
-            interface: &mut self.interface,
+    async fn receive_ack_async(&mut self) -> Result<(), Error<I::Error>> {
+        receive_ack_async(&mut self.interface).await
+    }
+
+    async fn receive_response_async(
+        &mut self,
+        sent_command: Command,
+        response_len: usize,
+    ) -> Result<&[u8], Error<I::Error>> {
+        let response_buf = self
+            .buf
+            .get_mut(..frame_size(response_len))
+            .ok_or(Error::BufTooSmall)?;
+        response_buf.fill(0); // zero out buf
+        self.interface.read(response_buf).await?;
+        let mut frame_len = response_buf.len();
+        if frame_pushed_out(response_buf) {
+            frame_len = self
+                .interface
+                .continue_frame(&mut self.buf, frame_len)
+                .await?;
         }
+        let frame = &self.buf[..frame_len];
+        self.skipped = skipped_bytes(frame);
+        let expected_response_command = sent_command as u8 + 1;
+        let range = parse_response(frame, expected_response_command)?;
+        Ok(&self.buf[range])
+    }
+
+    /// Send an ACK frame to force the PN532 to abort the current process.
+    /// In that case, the PN532 discontinues the last processing and does not answer anything
+    /// to the host controller.
+    /// Then, the PN532 starts again waiting for a new command.
+    pub async fn abort_async(&mut self) -> Result<(), Error<I::Error>> {
+        #[allow(const_item_mutation)]
+        self.interface.write(&mut ACK).await?;
+        Ok(())
     }
 }
 
+impl<I: AsyncInterface, D: AsyncDelay, const N: usize> Pn532<I, D, N> {
+    /// Send a request, wait for an ACK and then wait for a response.
+    ///
+    /// `response_len` is the largest expected length of the returned data.
+    ///
+    /// If no response arrives within `timeout`, the PN532 is told to [abort](Pn532::abort_async)
+    /// the current process.
+    ///
+    /// ```
+    /// # use pn532::doc_test_helper::get_async_pn532_with_delay;
+    /// use pn532::Request;
+    /// use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+    ///
+    /// let mut pn532 = get_async_pn532_with_delay();
+    /// let future = pn532.process_async(&Request::GET_FIRMWARE_VERSION, 4, 50.ms());
+    /// ```
+    #[inline]
+    pub async fn process_async<'a>(
+        &mut self,
+        request: impl IntoRequest<'a, N>,
+        response_len: usize,
+        timeout: D::Time,
+    ) -> Result<&[u8], Error<I::Error>> {
+        // codegen trampoline: https://github.com/rust-lang/rust/issues/77960
+        self._process_async(request.into_request(), response_len, timeout)
+            .await
+    }
+    async fn _process_async(
+        &mut self,
+        request: BorrowedRequest<'_>,
+        response_len: usize,
+        timeout: D::Time,
+    ) -> Result<&[u8], Error<I::Error>> {
+        let sent_command = request.command;
+        self.send_async(request).await?;
+        let mut ack_received = false;
+        let interface = &mut self.interface;
+        let ready: Option<Result<(), Error<I::Error>>> = with_timeout(
+            async {
+                interface.wait_ready().await?;
+                receive_ack_async(interface).await?;
+                ack_received = true;
+                interface.wait_ready().await?;
+                Ok(())
+            },
+            self.timer.delay(timeout),
+        )
+        .await;
+        match ready {
+            Some(result) => result?,
+            None if ack_received => {
+                self.abort_async().await?;
+                return Err(Error::TimeoutResponse);
+            }
+            None => return Err(Error::TimeoutAck),
+        }
+        self.receive_response_async(sent_command, response_len)
+            .await
+    }
+
+    /// Send a request and wait for an ACK.
+    ///
+    /// ```
+    /// # use pn532::doc_test_helper::get_async_pn532_with_delay;
+    /// use pn532::Request;
+    /// use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+    ///
+    /// let mut pn532 = get_async_pn532_with_delay();
+    /// let future = pn532.process_no_response_async(&Request::INLIST_ONE_ISO_A_TARGET, 5.ms());
+    /// ```
+    #[inline]
+    pub async fn process_no_response_async<'a>(
+        &mut self,
+        request: impl IntoRequest<'a, N>,
+        timeout: D::Time,
+    ) -> Result<(), Error<I::Error>> {
+        // codegen trampoline: https://github.com/rust-lang/rust/issues/77960
+        self._process_no_response_async(request.into_request(), timeout)
+            .await
+    }
+    async fn _process_no_response_async(
+        &mut self,
+        request: BorrowedRequest<'_>,
+        timeout: D::Time,
+    ) -> Result<(), Error<I::Error>> {
+        self.send_async(request).await?;
+        let interface = &mut self.interface;
+        let ready = with_timeout(
+            async {
+                interface.wait_ready().await?;
+                receive_ack_async(interface).await
+            },
+            self.timer.delay(timeout),
+        )
+        .await;
+        ready.unwrap_or(Err(Error::TimeoutAck))
+    }
+}
+
+async fn receive_ack_async<I: AsyncInterface>(interface: &mut I) -> Result<(), Error<I::Error>> {
+    let mut ack_buf = [0; 6];
+    interface.read(&mut ack_buf).await?;
+    if ack_buf != ACK {
+        Err(Error::BadAck)
+    } else {
+        Ok(())
+    }
+}
+
+/// Runs `future` to completion, or returns `None` if `timeout` completes first
+async fn with_timeout<F: Future>(
+    future: F,
+    timeout: impl Future<Output = ()>,
+) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut timeout = pin!(timeout);
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        timeout.as_mut().poll(cx).map(|()| None)
+    })
+    .await
+}
+
+/// Maps error codes of status bytes to [`Error::Status`] and [`Error::UnknownStatus`]
+fn response_error<E: Debug>(error: ResponseError) -> Error<E> {
+    match error {
+        ResponseError::Status(code) => {
+            ErrorCode::try_from(code).map_or(Error::UnknownStatus(code), Error::Status)
+        }
+        error => Error::Response(error),
+    }
+}
+
+/// Reads a frame in two phases: first the frame header, then exactly the remaining bytes of the frame.
+/// Leading garbage and stale ACK frames in front of the frame are read byte by byte and skipped.
+///
+/// Returns the number of bytes read.
+/// This only works for links that allow reading a frame in several chunks.
+pub(crate) fn read_frame_in_chunks<I: Interface>(
+    interface: &mut I,
+    buf: &mut [u8],
+) -> Result<usize, I::Error> {
+    continue_frame_in_chunks(interface, buf, 0)
+}
+
+/// Like [`read_frame_in_chunks`], but the first `len` bytes of `buf` were already read
+pub(crate) fn continue_frame_in_chunks<I: Interface>(
+    interface: &mut I,
+    buf: &mut [u8],
+    mut len: usize,
+) -> Result<usize, I::Error> {
+    while let Some(end) = next_chunk_end(buf, len) {
+        interface.read(&mut buf[len..end])?;
+        len = end;
+    }
+    Ok(len)
+}
+
+/// Async version of [`continue_frame_in_chunks`]
+#[cfg(feature = "async")]
+pub(crate) async fn continue_frame_in_chunks_async<I: AsyncInterface>(
+    interface: &mut I,
+    buf: &mut [u8],
+    mut len: usize,
+) -> Result<usize, I::Error> {
+    while let Some(end) = next_chunk_end(buf, len) {
+        interface.read(&mut buf[len..end]).await?;
+        len = end;
+    }
+    Ok(len)
+}
+
+/// Returns the end of the next chunk to read into `buf` after the first `len` bytes,
+/// or `None` if the frame is complete, its header is invalid or `buf` is full
+fn next_chunk_end(buf: &[u8], len: usize) -> Option<usize> {
+    let end = frame_end(&buf[..len])?.min(buf.len());
+    (end > len).then_some(end)
+}
+
+/// Returns the number of bytes needed to continue the frame in `read`
+/// or `None` if the frame header is invalid
+fn frame_end(read: &[u8]) -> Option<usize> {
+    // preamble, start code, LEN and LCS
+    if read.len() < 5 {
+        return Some(5);
+    }
+    let mut start = 0;
+    loop {
+        let Some(window) = read.get(start..start + 4) else {
+            return Some(start + 4);
+        };
+        if window[..2] != START_CODE {
+            start += 1;
+        } else if window[2..] == [0x00, 0xFF] {
+            // stale ACK frame
+            start += 4;
+        } else {
+            break;
+        }
+    }
+    let (frame_len, body_start) = if read[start + 2..start + 4] == EXTENDED_FRAME {
+        // 6.2.1.3 Extended information frame: LENM, LENL and LCS follow
+        let Some(&[len_msb, len_lsb, lcs]) = read.get(start + 4..start + 7) else {
+            return Some(start + 7);
+        };
+        if len_msb.wrapping_add(len_lsb).wrapping_add(lcs) != 0 {
+            return None;
+        }
+        (u16::from_be_bytes([len_msb, len_lsb]) as usize, start + 7)
+    } else {
+        if read[start + 2].wrapping_add(read[start + 3]) != 0 {
+            return None;
+        }
+        (read[start + 2] as usize, start + 4)
+    };
+    // frame identifier + data, DCS and postamble
+    Some(body_start + frame_len + 2)
+}
+
+/// Returns the position of the start code of the first frame in `buf` that is not an ACK frame
+fn find_start_code(buf: &[u8]) -> Option<usize> {
+    let mut start = 0;
+    while let Some(window) = buf.get(start..start + 4) {
+        if window[..2] != START_CODE {
+            start += 1;
+        } else if window[2..] == [0x00, 0xFF] {
+            // stale ACK frame
+            start += 4;
+        } else {
+            return Some(start);
+        }
+    }
+    None
+}
+
+/// Returns `true` if garbage or a stale ACK frame in front of the frame in `buf`
+/// pushed the end of the frame out of `buf`.
+/// Without a start code in `buf` there is no frame to continue.
+fn frame_pushed_out(buf: &[u8]) -> bool {
+    let mut start = 0;
+    while let Some(code) = buf.get(start..start + 2) {
+        if code != START_CODE {
+            start += 1;
+        } else if buf.get(start + 2..start + 4) == Some(&[0x00, 0xFF]) {
+            // stale ACK frame
+            start += 4;
+        } else {
+            // LEN and LCS may be cut off
+            return start > 1 || (start == 1 && buf[0] != PREAMBLE[0]);
+        }
+    }
+    false
+}
+
+/// Returns the number of bytes in front of the first frame in `buf`, not counting a single preamble byte
+fn skipped_bytes(buf: &[u8]) -> usize {
+    match find_start_code(buf) {
+        None => buf.len(),
+        Some(start) if start > 0 && buf[start - 1] == PREAMBLE[0] => start - 1,
+        Some(start) => start,
+    }
+}
+
+/// Writes the information frame of `request` into `buf` and returns the length of the frame
+fn write_frame<E: Debug>(buf: &mut [u8], request: BorrowedRequest<'_>) -> Result<usize, Error<E>> {
+    let data_len = request.data.len();
+    let frame_len = 2 + data_len; // frame identifier + command + data
+    if frame_size(data_len) > buf.len() || frame_len > u16::MAX as usize {
+        return Err(Error::RequestTooLarge);
+    }
+
+    let mut data_sum = HOST_TO_PN532.wrapping_add(request.command as u8); // sum(command + data + frame identifier)
+    for &byte in request.data {
+        data_sum = data_sum.wrapping_add(byte);
+    }
+
+    const fn to_checksum(sum: u8) -> u8 {
+        (!sum).wrapping_add(1)
+    }
+
+    buf[0] = PREAMBLE[0];
+    buf[1] = PREAMBLE[1];
+    buf[2] = PREAMBLE[2];
+    let header_len = if frame_len > MAX_NORMAL_FRAME_LEN {
+        // 6.2.1.3 Extended information frame
+        let [len_msb, len_lsb] = (frame_len as u16).to_be_bytes();
+        buf[3] = EXTENDED_FRAME[0];
+        buf[4] = EXTENDED_FRAME[1];
+        buf[5] = len_msb;
+        buf[6] = len_lsb;
+        buf[7] = to_checksum(len_msb.wrapping_add(len_lsb));
+        8
+    } else {
+        buf[3] = frame_len as u8;
+        buf[4] = to_checksum(frame_len as u8);
+        5
+    };
+    buf[header_len] = HOST_TO_PN532;
+    buf[header_len + 1] = request.command as u8;
+
+    let data_start = header_len + 2;
+    buf[data_start..data_start + data_len].copy_from_slice(request.data);
+
+    buf[data_start + data_len] = to_checksum(data_sum);
+    buf[data_start + data_len + 1] = POSTAMBLE;
+    Ok(data_start + data_len + 2)
+}
+
+/// Returns the range of the response data in `response_buf`
 fn parse_response<E: Debug>(
     response_buf: &[u8],
     expected_response_command: u8,
-) -> Result<&[u8], Error<E>> {
-    if response_buf[0..3] != PREAMBLE {
-        return Err(Error::BadResponseFrame);
+) -> Result<Range<usize>, Error<E>> {
+    if response_buf.len() < 5 {
+        return Err(Error::BufTooSmall);
     }
+    // Skip leading garbage and stale ACK frames
+    let start = find_start_code(response_buf).ok_or(Error::BadResponseFrame)?;
     // Check length & length checksum
-    let frame_len = response_buf[3];
-    if (frame_len.wrapping_add(response_buf[4])) != 0 {
-        return Err(Error::CrcError);
-    }
+    let (frame_len, body_start) = if response_buf[start + 2..start + 4] == EXTENDED_FRAME {
+        // 6.2.1.3 Extended information frame
+        if response_buf.len() < start + 7 {
+            return Err(Error::BufTooSmall);
+        }
+        let (len_msb, len_lsb) = (response_buf[start + 4], response_buf[start + 5]);
+        if len_msb
+            .wrapping_add(len_lsb)
+            .wrapping_add(response_buf[start + 6])
+            != 0
+        {
+            return Err(Error::CrcError);
+        }
+        (u16::from_be_bytes([len_msb, len_lsb]) as usize, start + 7)
+    } else {
+        let frame_len = response_buf[start + 2];
+        if (frame_len.wrapping_add(response_buf[start + 3])) != 0 {
+            return Err(Error::CrcError);
+        }
+        (frame_len as usize, start + 4)
+    };
     if frame_len == 0 {
         return Err(Error::BadResponseFrame);
     }
@@ -428,7 +1095,7 @@ fn parse_response<E: Debug>(
         // 6.2.1.5 Error frame
         return Err(Error::Syntax);
     }
-    match response_buf.get(5 + frame_len as usize + 1) {
+    match response_buf.get(body_start + frame_len + 1) {
         None => {
             return Err(Error::BufTooSmall);
         }
@@ -438,32 +1105,443 @@ fn parse_response<E: Debug>(
         }
     }
 
-    if response_buf[5] != PN532_TO_HOST || response_buf[6] != expected_response_command {
+    if response_buf[body_start] != PN532_TO_HOST
+        || response_buf[body_start + 1] != expected_response_command
+    {
         return Err(Error::BadResponseFrame);
     }
     // Check frame checksum value matches bytes
-    let checksum = response_buf[5..5 + frame_len as usize + 1]
+    let checksum = response_buf[body_start..body_start + frame_len + 1]
         .iter()
         .fold(0u8, |s, &b| s.wrapping_add(b));
     if checksum != 0 {
         return Err(Error::CrcError);
     }
-    // Adjust response buf and return it
-    Ok(&response_buf[7..5 + frame_len as usize])
+    Ok(body_start + 2..body_start + frame_len)
 }
 
-struct WaitReadyFuture<'a, I> {
-    interface: &'a mut I,
-}
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::doc_test_helper::NoOpTimer;
+    use crate::requests::Request;
+    use crate::test_helper::block_on;
+    use core::time::Duration;
+    use std::collections::VecDeque;
+
+    pub const FIRMWARE_VERSION_RESPONSE: [u8; 13] = [
+        0x00, 0x00, 0xFF, 0x06, 0xFA, 0xD5, 0x03, 0x32, 0x01, 0x06, 0x07, 0xE8, 0x00,
+    ];
+
+    /// Records written frames and returns the frames in `reads` like the SPI link:
+    /// every read continues the current frame, bytes beyond the end of the frame are zero.
+    /// Is ready as long as there are frames left.
+    #[derive(Default)]
+    pub struct FrameInterface {
+        pub written: Vec<u8>,
+        pub reads: VecDeque<Vec<u8>>,
+    }
+
+    impl Interface for FrameInterface {
+        type Error = Infallible;
+
+        fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+            self.written.extend_from_slice(frame);
+            Ok(())
+        }
+
+        fn wait_ready(&mut self) -> Poll<Result<(), Self::Error>> {
+            if self.reads.is_empty() {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+            buf.fill(0);
+            if let Some(frame) = self.reads.front_mut() {
+                let len = buf.len().min(frame.len());
+                buf[..len].copy_from_slice(&frame[..len]);
+                frame.drain(..len);
+                if frame.is_empty() || len < buf.len() {
+                    self.reads.pop_front();
+                }
+            }
+            Ok(())
+        }
+
+        fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            read_frame_in_chunks(self, buf)
+        }
 
-impl<I: Interface> Future for WaitReadyFuture<'_, I> {
-    type Output = Result<(), I::Error>;
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let poll = self.interface.wait_ready();
-        if poll.is_pending() {
-            // tell the executor to poll this future again
-            cx.waker().wake_by_ref();
+        fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+            continue_frame_in_chunks(self, buf, len)
         }
-        poll
+    }
+
+    /// Test double for an I2C link:
+    /// every read restarts at the first byte of the current frame, bytes beyond the end of the frame are zero.
+    /// The frame is dropped once it was read completely.
+    #[derive(Default)]
+    struct RestartInterface {
+        reads: VecDeque<Vec<u8>>,
+        read_calls: usize,
+    }
+
+    impl Interface for RestartInterface {
+        type Error = Infallible;
+
+        fn write(&mut self, _frame: &mut [u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn wait_ready(&mut self) -> Poll<Result<(), Self::Error>> {
+            if self.reads.is_empty() {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+            self.read_calls += 1;
+            buf.fill(0);
+            if let Some(frame) = self.reads.front() {
+                let len = buf.len().min(frame.len());
+                buf[..len].copy_from_slice(&frame[..len]);
+                if len == frame.len() {
+                    self.reads.pop_front();
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn pn532<const N: usize>(reads: Vec<Vec<u8>>) -> Pn532<FrameInterface, (), N> {
+        Pn532::new(
+            FrameInterface {
+                written: Vec::new(),
+                reads: reads.into(),
+            },
+            (),
+        )
+    }
+
+    /// Times out as soon as it is polled
+    struct ImmediateDelay;
+
+    impl AsyncDelay for ImmediateDelay {
+        type Time = ();
+
+        async fn delay(&mut self, _: Self::Time) {}
+    }
+
+    #[test]
+    fn test_send_normal_frame() {
+        let mut pn532 = pn532::<32>(vec![]);
+        pn532.send(&Request::GET_FIRMWARE_VERSION).unwrap();
+        assert_eq!(
+            pn532.interface.written,
+            [0x00, 0x00, 0xFF, 0x02, 0xFE, 0xD4, 0x02, 0x2A, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_send_largest_normal_frame() {
+        let mut pn532 = pn532::<300>(vec![]);
+        let data = [0x01; 252];
+        pn532
+            .send(BorrowedRequest::new(Command::InDataExchange, &data))
+            .unwrap();
+        let frame = &pn532.interface.written;
+        assert_eq!(frame.len(), 252 + 9);
+        assert_eq!(frame[..7], [0x00, 0x00, 0xFF, 0xFE, 0x02, 0xD4, 0x40]);
+    }
+
+    #[test]
+    fn test_send_extended_frame() {
+        let mut pn532 = pn532::<320>(vec![]);
+        let data = [0x01; 300];
+        pn532
+            .send(BorrowedRequest::new(Command::InDataExchange, &data))
+            .unwrap();
+        let frame = &pn532.interface.written;
+        assert_eq!(frame.len(), 300 + 12);
+        // LEN = 302 = 0x012E, LCS = 0x100 - (0x01 + 0x2E) = 0xD1
+        assert_eq!(
+            frame[..10],
+            [0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x01, 0x2E, 0xD1, 0xD4, 0x40]
+        );
+        assert!(frame[10..310].iter().all(|&b| b == 0x01));
+        // DCS = 0x100 - ((0xD4 + 0x40 + 300) & 0xFF) = 0x100 - 0x40 = 0xC0
+        assert_eq!(frame[310..], [0xC0, 0x00]);
+    }
+
+    fn extended_response(lcs: u8) -> Vec<u8> {
+        // 0xD5 0x41 0x00 followed by 297 bytes of 0x02: LEN = 300 = 0x012C
+        let mut frame = vec![
+            0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x01, 0x2C, lcs, 0xD5, 0x41, 0x00,
+        ];
+        frame.extend_from_slice(&[0x02; 297]);
+        // DCS = 0x100 - ((0xD5 + 0x41 + 297 * 2) & 0xFF) = 0x100 - 0x68 = 0x98
+        frame.extend_from_slice(&[0x98, 0x00]);
+        frame
+    }
+
+    #[test]
+    fn test_receive_extended_frame() {
+        let mut pn532 = pn532::<320>(vec![extended_response(0xD3)]);
+        let response = pn532
+            .receive_response(Command::InDataExchange, 298)
+            .unwrap();
+        assert_eq!(response.len(), 298);
+        assert_eq!(response[0], 0x00);
+        assert!(response[1..].iter().all(|&b| b == 0x02));
+    }
+
+    #[test]
+    fn test_receive_extended_frame_bad_length_checksum() {
+        let mut pn532 = pn532::<320>(vec![extended_response(0xD4)]);
+        assert_eq!(
+            pn532.receive_response(Command::InDataExchange, 298),
+            Err(Error::CrcError)
+        );
+    }
+
+    #[test]
+    fn test_receive_normal_frame() {
+        let mut pn532 = pn532::<32>(vec![FIRMWARE_VERSION_RESPONSE.to_vec()]);
+        let response = pn532
+            .receive_response(Command::GetFirmwareVersion, 4)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+    }
+
+    #[test]
+    fn test_process_async() {
+        let interface = FrameInterface {
+            written: Vec::new(),
+            reads: vec![ACK.to_vec(), FIRMWARE_VERSION_RESPONSE.to_vec()].into(),
+        };
+        let mut pn532 = Pn532::<_, _, 32>::new(interface, ImmediateDelay);
+        let response =
+            block_on(pn532.process_async(&Request::GET_FIRMWARE_VERSION, 4, ())).unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+    }
+
+    #[test]
+    fn test_process_async_timeout_ack() {
+        let mut pn532 = Pn532::<_, _, 32>::new(FrameInterface::default(), ImmediateDelay);
+        let result = block_on(pn532.process_async(&Request::GET_FIRMWARE_VERSION, 4, ()));
+        assert_eq!(result, Err(Error::TimeoutAck));
+        let result = block_on(pn532.process_no_response_async(&Request::GET_FIRMWARE_VERSION, ()));
+        assert_eq!(result, Err(Error::TimeoutAck));
+    }
+
+    #[test]
+    fn test_process_async_timeout_response() {
+        let interface = FrameInterface {
+            written: Vec::new(),
+            reads: vec![ACK.to_vec()].into(),
+        };
+        let mut pn532 = Pn532::<_, _, 32>::new(interface, ImmediateDelay);
+        let result = block_on(pn532.process_async(&Request::GET_FIRMWARE_VERSION, 4, ()));
+        assert_eq!(result, Err(Error::TimeoutResponse));
+        // the command is followed by an ACK to abort the command
+        assert!(pn532.interface.written.ends_with(&ACK));
+    }
+
+    #[test]
+    fn test_receive_response_auto() {
+        let mut pn532 = pn532::<32>(vec![FIRMWARE_VERSION_RESPONSE.to_vec(), vec![0xAA]]);
+        let response = pn532
+            .receive_response_auto(Command::GetFirmwareVersion)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+        // the following frame was not touched
+        assert_eq!(pn532.interface.reads, [vec![0xAA]]);
+    }
+
+    #[test]
+    fn test_receive_response_auto_extended_frame() {
+        let mut pn532 = pn532::<320>(vec![extended_response(0xD3)]);
+        let response = pn532
+            .receive_response_auto(Command::InDataExchange)
+            .unwrap();
+        assert_eq!(response.len(), 298);
+        assert!(pn532.interface.reads.is_empty());
+    }
+
+    #[test]
+    fn test_receive_response_auto_bad_length_checksum() {
+        let mut pn532 = pn532::<320>(vec![extended_response(0xD4)]);
+        assert_eq!(
+            pn532.receive_response_auto(Command::InDataExchange),
+            Err(Error::CrcError)
+        );
+    }
+
+    #[test]
+    fn test_process_auto() {
+        let interface = FrameInterface {
+            written: Vec::new(),
+            reads: vec![ACK.to_vec(), FIRMWARE_VERSION_RESPONSE.to_vec()].into(),
+        };
+        let mut pn532 = Pn532::<_, _, 32>::new(interface, NoOpTimer);
+        let response = pn532
+            .process_auto(&Request::GET_FIRMWARE_VERSION, Duration::ZERO)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+    }
+
+    #[test]
+    fn test_retransmit_corrupted_response() {
+        let mut corrupted = FIRMWARE_VERSION_RESPONSE;
+        corrupted[8] ^= 0xFF;
+        let reads = || {
+            vec![
+                ACK.to_vec(),
+                corrupted.to_vec(),
+                FIRMWARE_VERSION_RESPONSE.to_vec(),
+            ]
+        };
+
+        let mut pn532 = Pn532::<_, _, 32>::new(
+            FrameInterface {
+                written: Vec::new(),
+                reads: reads().into(),
+            },
+            NoOpTimer,
+        );
+        let result = pn532.process(&Request::GET_FIRMWARE_VERSION, 4, Duration::ZERO);
+        assert!(matches!(result, Err(Error::CrcError)));
+
+        let mut pn532 = Pn532::<_, _, 32>::new(
+            FrameInterface {
+                written: Vec::new(),
+                reads: reads().into(),
+            },
+            NoOpTimer,
+        );
+        pn532.set_retransmits(1);
+        let response = pn532
+            .process(&Request::GET_FIRMWARE_VERSION, 4, Duration::ZERO)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+        assert!(pn532.interface.written.ends_with(&NACK));
+    }
+
+    #[test]
+    fn test_receive_response_with_leading_garbage() {
+        let mut frame = vec![0x00, 0x00, 0x00];
+        frame.extend_from_slice(&ACK);
+        frame.extend_from_slice(&FIRMWARE_VERSION_RESPONSE);
+
+        let mut driver = pn532::<32>(vec![frame.clone()]);
+        let response = driver
+            .receive_response(Request::GET_FIRMWARE_VERSION.command, 4)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+        assert_eq!(driver.skipped_bytes(), 9);
+        assert!(driver.interface.reads.is_empty());
+
+        let mut driver = pn532::<32>(vec![frame.clone()]);
+        let response = driver
+            .receive_response(Request::GET_FIRMWARE_VERSION.command, 13)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+        assert_eq!(driver.skipped_bytes(), 9);
+
+        let mut driver = pn532::<32>(vec![frame.clone()]);
+        let response = driver
+            .receive_response_auto(Request::GET_FIRMWARE_VERSION.command)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+        assert_eq!(driver.skipped_bytes(), 9);
+        assert!(driver.interface.reads.is_empty());
+
+        let mut driver = Pn532::<_, _, 32>::new(FrameInterface::default(), ImmediateDelay);
+        driver.interface.reads.extend([ACK.to_vec(), frame]);
+        let response =
+            block_on(driver.process_async(&Request::GET_FIRMWARE_VERSION, 4, ())).unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+        assert_eq!(driver.skipped_bytes(), 9);
+        assert!(driver.interface.reads.is_empty());
+
+        // without a start code there is no frame to continue
+        let mut driver = pn532::<32>(vec![vec![0x00; 30]]);
+        assert_eq!(
+            driver.receive_response(Request::GET_FIRMWARE_VERSION.command, 4),
+            Err(Error::BadResponseFrame)
+        );
+        assert_eq!(driver.interface.reads[0].len(), 30 - frame_size(4));
+    }
+
+    #[test]
+    fn test_receive_response_with_leading_garbage_restarting_link() {
+        let mut frame = vec![0x00, 0x00, 0x00];
+        frame.extend_from_slice(&ACK);
+        frame.extend_from_slice(&FIRMWARE_VERSION_RESPONSE);
+
+        let mut driver = Pn532::<_, _, 32>::new(RestartInterface::default(), ());
+        driver.interface.reads.push_back(frame.clone());
+        assert_eq!(
+            driver.receive_response(Request::GET_FIRMWARE_VERSION.command, 4),
+            Err(Error::BadResponseFrame)
+        );
+        assert_eq!(driver.interface.read_calls, 1);
+        // the frame fits into the read bytes
+        let response = driver
+            .receive_response(Request::GET_FIRMWARE_VERSION.command, 13)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+        assert_eq!(driver.skipped_bytes(), 9);
+
+        let mut driver = Pn532::<_, _, 32>::new(RestartInterface::default(), ImmediateDelay);
+        driver.interface.reads.extend([ACK.to_vec(), frame]);
+        let result = block_on(driver.process_async(&Request::GET_FIRMWARE_VERSION, 4, ()));
+        assert_eq!(result, Err(Error::BadResponseFrame));
+        assert_eq!(driver.interface.read_calls, 2);
+    }
+
+    #[test]
+    fn test_receive_response_without_preamble() {
+        let mut driver = pn532::<32>(vec![FIRMWARE_VERSION_RESPONSE[1..].to_vec()]);
+        let response = driver
+            .receive_response_auto(Request::GET_FIRMWARE_VERSION.command)
+            .unwrap();
+        assert_eq!(response, [0x32, 0x01, 0x06, 0x07]);
+        assert_eq!(driver.skipped_bytes(), 0);
+    }
+
+    #[test]
+    fn test_process_status() {
+        // InDataExchange response with status 0x54: authentication error and MI bit
+        let response = vec![0x00, 0x00, 0xFF, 0x03, 0xFD, 0xD5, 0x41, 0x54, 0x96, 0x00];
+        let interface = FrameInterface {
+            written: Vec::new(),
+            reads: vec![ACK.to_vec(), response].into(),
+        };
+        let mut pn532 = Pn532::<_, _, 32>::new(interface, NoOpTimer);
+        let result = pn532.process_status(&Request::ntag_read(4), 16, Duration::ZERO);
+        assert_eq!(result, Err(Error::Status(ErrorCode::AuthenticationError)));
+    }
+
+    #[test]
+    fn test_request_and_response_too_large() {
+        let mut pn532 = pn532::<32>(vec![]);
+        let data = [0; 24];
+        let request = BorrowedRequest::new(Command::InDataExchange, &data);
+        assert_eq!(pn532.send(request), Err(Error::RequestTooLarge));
+        assert!(pn532.interface.written.is_empty());
+        assert_eq!(
+            pn532.receive_response(Command::InDataExchange, 24),
+            Err(Error::BufTooSmall)
+        );
+        assert_eq!(
+            pn532.receive_response(Command::InDataExchange, usize::MAX),
+            Err(Error::BufTooSmall)
+        );
     }
 }
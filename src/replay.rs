@@ -0,0 +1,443 @@
+//! Recording and replaying the traffic on the serial link
+//!
+//! [`RecordingInterface`] wraps an [`Interface`] and records every call into a [`Transcript`].
+//! A `Transcript` can be saved as text and played back by a [`ReplayInterface`],
+//! which fails as soon as the host controller diverges from the recording.
+//! This allows to turn sessions with real hardware into regression tests that run without hardware.
+//!
+//! ```
+//! # use pn532::doc_test_helper::NoOpTimer;
+//! use pn532::replay::{RecordingInterface, ReplayInterface, Transcript};
+//! use pn532::simulator::SimulatedPn532;
+//! use pn532::{Pn532, Request};
+//! use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+//!
+//! // record, e.g. with a `SerialPortInterface`
+//! let interface = RecordingInterface::new(SimulatedPn532::new());
+//! let mut pn532: Pn532<_, _, 32> = Pn532::new(interface, NoOpTimer);
+//! pn532.process(&Request::GET_FIRMWARE_VERSION, 4, 50.ms()).unwrap();
+//! let text = pn532.interface.transcript.to_string();
+//!
+//! // replay
+//! let transcript: Transcript = text.parse().unwrap();
+//! let mut pn532: Pn532<_, _, 32> = Pn532::new(ReplayInterface::new(transcript), NoOpTimer);
+//! let version = pn532.process(&Request::GET_FIRMWARE_VERSION, 4, 50.ms()).unwrap();
+//! assert_eq!(version, [0x32, 0x01, 0x06, 0x07]);
+//! ```
+use crate::Interface;
+use core::fmt;
+use core::str::FromStr;
+use core::task::Poll;
+
+/// A single call to an [`Interface`]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Event {
+    /// `write` with the written frame
+    Write(Vec<u8>),
+    /// `wait_ready` returned `Poll::Pending`, repeated calls are recorded only once
+    Pending,
+    /// `wait_ready` returned `Poll::Ready`
+    Ready,
+    /// `read` or `read_frame` with the bytes read
+    Read(Vec<u8>),
+}
+
+/// Recorded calls to an [`Interface`]
+///
+/// The text format has one event per line:
+/// `write` or `read` followed by the bytes in hex, `pending` or `ready`.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// ```text
+/// write 00 00 FF 02 FE D4 02 2A 00
+/// pending
+/// ready
+/// read 00 00 FF 00 FF 00
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Transcript {
+    /// Events in the order of the calls
+    pub events: Vec<Event>,
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            let (name, bytes) = match event {
+                Event::Write(bytes) => ("write", bytes.as_slice()),
+                Event::Pending => ("pending", &[][..]),
+                Event::Ready => ("ready", &[][..]),
+                Event::Read(bytes) => ("read", bytes.as_slice()),
+            };
+            f.write_str(name)?;
+            for byte in bytes {
+                write!(f, " {byte:02X}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error of parsing a [`Transcript`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ParseTranscriptError {
+    /// Number of the invalid line, starting at 1
+    pub line: usize,
+}
+
+impl FromStr for Transcript {
+    type Err = ParseTranscriptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut events = Vec::new();
+        for (index, line) in s.lines().enumerate() {
+            let error = ParseTranscriptError { line: index + 1 };
+            let mut words = line.split_whitespace();
+            let Some(name) = words.next() else {
+                continue;
+            };
+            if name.starts_with('#') {
+                continue;
+            }
+            let bytes = words
+                .map(|word| u8::from_str_radix(word, 16))
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|_| error)?;
+            let event = match name {
+                "write" => Event::Write(bytes),
+                "read" => Event::Read(bytes),
+                "pending" if bytes.is_empty() => Event::Pending,
+                "ready" if bytes.is_empty() => Event::Ready,
+                _ => return Err(error),
+            };
+            events.push(event);
+        }
+        Ok(Transcript { events })
+    }
+}
+
+/// [`Interface`] that records every successful call to `interface` in `transcript`
+///
+/// Calls that return an error are passed through without being recorded.
+#[derive(Clone, Debug)]
+pub struct RecordingInterface<I> {
+    /// Recorded interface
+    pub interface: I,
+    /// Calls recorded so far
+    pub transcript: Transcript,
+}
+
+impl<I> RecordingInterface<I> {
+    /// Records the calls to `interface` into an empty transcript
+    pub fn new(interface: I) -> Self {
+        RecordingInterface {
+            interface,
+            transcript: Transcript::default(),
+        }
+    }
+}
+
+impl<I: Interface> Interface for RecordingInterface<I> {
+    type Error = I::Error;
+
+    fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        // record before the frame is modified by the interface, e.g. with the `msb-spi` feature
+        let event = Event::Write(frame.to_vec());
+        self.interface.write(frame)?;
+        self.transcript.events.push(event);
+        Ok(())
+    }
+
+    fn wait_ready(&mut self) -> Poll<Result<(), Self::Error>> {
+        let event = match self.interface.wait_ready()? {
+            Poll::Ready(()) => Event::Ready,
+            Poll::Pending => Event::Pending,
+        };
+        if event == Event::Ready || self.transcript.events.last() != Some(&Event::Pending) {
+            self.transcript.events.push(event);
+        }
+        if self.transcript.events.last() == Some(&Event::Ready) {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.interface.read(buf)?;
+        self.transcript.events.push(Event::Read(buf.to_vec()));
+        Ok(())
+    }
+
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = self.interface.read_frame(buf)?;
+        self.transcript
+            .events
+            .push(Event::Read(buf[..len].to_vec()));
+        Ok(len)
+    }
+
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        let continued_len = self.interface.continue_frame(buf, len)?;
+        if continued_len > len {
+            self.transcript
+                .events
+                .push(Event::Read(buf[len..continued_len].to_vec()));
+        }
+        Ok(continued_len)
+    }
+}
+
+/// The host controller diverged from the [`Transcript`] of a [`ReplayInterface`]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ReplayError {
+    /// Index of the expected event in the transcript
+    pub index: usize,
+    /// Recorded event or `None` at the end of the transcript
+    pub expected: Option<Event>,
+    /// Call of the host controller, for reads with the number of requested bytes set to zero
+    pub actual: Event,
+}
+
+/// [`Interface`] that plays back a [`Transcript`]
+///
+/// Written frames must match the recorded frames and reads must request the recorded number of bytes.
+/// Recorded `pending` events are skipped because they depend on the timing of the recording.
+/// `wait_ready` returns `Poll::Pending` if the next recorded event is a write,
+/// which is the case if the recorded host controller timed out.
+#[derive(Clone, Debug)]
+pub struct ReplayInterface {
+    /// Transcript that is played back
+    pub transcript: Transcript,
+    position: usize,
+}
+
+impl ReplayInterface {
+    /// Plays back `transcript` from the first event
+    pub fn new(transcript: Transcript) -> Self {
+        ReplayInterface {
+            transcript,
+            position: 0,
+        }
+    }
+
+    /// Returns `true` if all events of the transcript were played back
+    pub fn is_finished(&mut self) -> bool {
+        self.skip_pending();
+        self.position == self.transcript.events.len()
+    }
+
+    fn skip_pending(&mut self) {
+        while self.transcript.events.get(self.position) == Some(&Event::Pending) {
+            self.position += 1;
+        }
+    }
+
+    fn next(&mut self, actual: Event) -> Result<&Event, ReplayError> {
+        self.skip_pending();
+        let expected = self.transcript.events.get(self.position);
+        let matches = match (expected, &actual) {
+            (Some(Event::Write(expected)), Event::Write(actual)) => expected == actual,
+            (Some(Event::Read(_)), Event::Read(_)) | (Some(Event::Ready), Event::Ready) => true,
+            _ => false,
+        };
+        if !matches {
+            return Err(ReplayError {
+                index: self.position,
+                expected: expected.cloned(),
+                actual,
+            });
+        }
+        self.position += 1;
+        Ok(&self.transcript.events[self.position - 1])
+    }
+
+    fn read_error(&self, requested: usize) -> ReplayError {
+        ReplayError {
+            index: self.position - 1,
+            expected: self.transcript.events.get(self.position - 1).cloned(),
+            actual: Event::Read(vec![0; requested]),
+        }
+    }
+}
+
+impl Interface for ReplayInterface {
+    type Error = ReplayError;
+
+    fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        self.next(Event::Write(frame.to_vec()))?;
+        Ok(())
+    }
+
+    fn wait_ready(&mut self) -> Poll<Result<(), Self::Error>> {
+        self.skip_pending();
+        match self.transcript.events.get(self.position) {
+            Some(Event::Write(_)) | None => Poll::Pending,
+            _ => Poll::Ready(self.next(Event::Ready).map(|_| ())),
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        match self.next(Event::Read(vec![0; buf.len()]))? {
+            Event::Read(bytes) if bytes.len() == buf.len() => {
+                buf.copy_from_slice(bytes);
+                Ok(())
+            }
+            _ => Err(self.read_error(buf.len())),
+        }
+    }
+
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        match self.next(Event::Read(vec![0; buf.len()]))? {
+            Event::Read(bytes) if bytes.len() <= buf.len() => {
+                let len = bytes.len();
+                buf[..len].copy_from_slice(bytes);
+                Ok(len)
+            }
+            _ => Err(self.read_error(buf.len())),
+        }
+    }
+
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        // a recorded continuation that read nothing left no event
+        self.skip_pending();
+        if !matches!(
+            self.transcript.events.get(self.position),
+            Some(Event::Read(_))
+        ) {
+            return Ok(len);
+        }
+        match self.next(Event::Read(vec![0; buf.len() - len]))? {
+            Event::Read(bytes) if bytes.len() <= buf.len() - len => {
+                let continued_len = len + bytes.len();
+                buf[len..continued_len].copy_from_slice(bytes);
+                Ok(continued_len)
+            }
+            _ => Err(self.read_error(buf.len() - len)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc_test_helper::NoOpTimer;
+    use crate::protocol::tests::{FrameInterface, FIRMWARE_VERSION_RESPONSE};
+    use crate::protocol::Error;
+    use crate::protocol::ACK;
+    use crate::requests::SAMMode;
+    use crate::simulator::SimulatedPn532;
+    use crate::{Pn532, Request};
+    use core::time::Duration;
+
+    #[test]
+    fn test_record() {
+        let interface = RecordingInterface::new(FrameInterface::default());
+        let mut pn532 = Pn532::<_, _, 32>::new(interface, ());
+        pn532.send(&Request::GET_FIRMWARE_VERSION).unwrap();
+        for _ in 0..3 {
+            assert!(pn532.interface.wait_ready().is_pending());
+        }
+        let reads = &mut pn532.interface.interface.reads;
+        reads.extend([ACK.to_vec(), FIRMWARE_VERSION_RESPONSE.to_vec()]);
+        assert!(pn532.interface.wait_ready().is_ready());
+        pn532.receive_ack().unwrap();
+        assert!(pn532.interface.wait_ready().is_ready());
+        pn532
+            .receive_response(Request::GET_FIRMWARE_VERSION.command, 4)
+            .unwrap();
+
+        let transcript = pn532.interface.transcript;
+        assert_eq!(
+            transcript.to_string(),
+            "write 00 00 FF 02 FE D4 02 2A 00\n\
+             pending\n\
+             ready\n\
+             read 00 00 FF 00 FF 00\n\
+             ready\n\
+             read 00 00 FF 06 FA D5 03 32 01 06 07 E8 00\n"
+        );
+        assert_eq!(transcript.to_string().parse(), Ok(transcript));
+    }
+
+    #[test]
+    fn test_replay_continued_frame() {
+        let interface = RecordingInterface::new(FrameInterface::default());
+        let mut pn532 = Pn532::<_, _, 32>::new(interface, ());
+        let mut frame = vec![0xAA, 0xAA];
+        frame.extend_from_slice(&FIRMWARE_VERSION_RESPONSE);
+        pn532.interface.interface.reads.push_back(frame);
+        assert!(pn532.interface.wait_ready().is_ready());
+        let response = pn532.receive_response(Request::GET_FIRMWARE_VERSION.command, 4);
+        assert_eq!(response, Ok(&[0x32, 0x01, 0x06, 0x07][..]));
+        let transcript = pn532.interface.transcript;
+        assert_eq!(
+            transcript.to_string(),
+            "ready\n\
+             read AA AA 00 00 FF 06 FA D5 03 32 01 06 07\n\
+             read E8 00\n"
+        );
+
+        let mut pn532 = Pn532::<_, _, 32>::new(ReplayInterface::new(transcript), ());
+        assert!(pn532.interface.wait_ready().is_ready());
+        let response = pn532.receive_response(Request::GET_FIRMWARE_VERSION.command, 4);
+        assert_eq!(response, Ok(&[0x32, 0x01, 0x06, 0x07][..]));
+        assert!(pn532.interface.is_finished());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let text = "# comment\n\nwrite 00 FF\nready 00\n";
+        assert_eq!(
+            text.parse::<Transcript>(),
+            Err(ParseTranscriptError { line: 4 })
+        );
+        assert_eq!(
+            "read 0G".parse::<Transcript>(),
+            Err(ParseTranscriptError { line: 1 })
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let interface = RecordingInterface::new(SimulatedPn532::new());
+        let mut pn532 = Pn532::<_, _, 32>::new(interface, NoOpTimer);
+        pn532
+            .process(&Request::GET_FIRMWARE_VERSION, 4, Duration::ZERO)
+            .unwrap();
+        pn532
+            .process(
+                &Request::sam_configuration(SAMMode::Normal, false),
+                0,
+                Duration::ZERO,
+            )
+            .unwrap();
+        let transcript = pn532.interface.transcript;
+
+        let mut pn532 = Pn532::<_, _, 32>::new(ReplayInterface::new(transcript), NoOpTimer);
+        let version = pn532.process(&Request::GET_FIRMWARE_VERSION, 4, Duration::ZERO);
+        assert_eq!(version, Ok(&[0x32, 0x01, 0x06, 0x07][..]));
+        let result = pn532.process(&Request::GET_FIRMWARE_VERSION, 4, Duration::ZERO);
+        let Err(Error::InterfaceError(error)) = result else {
+            panic!("expected a replay error, got {result:?}");
+        };
+        assert_eq!(error.index, 5);
+        assert_eq!(
+            error.actual,
+            Event::Write(vec![0, 0, 0xFF, 0x02, 0xFE, 0xD4, 0x02, 0x2A, 0x00])
+        );
+        assert!(!pn532.interface.is_finished());
+    }
+
+    #[test]
+    fn test_replay_timeout() {
+        let transcript: Transcript = "write 00 00 FF 02 FE D4 02 2A 00\npending\n"
+            .parse()
+            .unwrap();
+        let mut pn532 = Pn532::<_, _, 32>::new(ReplayInterface::new(transcript), NoOpTimer);
+        let result = pn532.process(&Request::GET_FIRMWARE_VERSION, 4, Duration::ZERO);
+        assert_eq!(result, Err(Error::TimeoutAck));
+        assert!(pn532.interface.is_finished());
+    }
+}
@@ -1,4 +1,7 @@
 //! Pn532 Requests
+use crate::mifare::KeyType;
+use crate::protocol::{frame_size, Error};
+use core::fmt::Debug;
 
 /// Pn532 Request consisting of a [`Command`] and extra command data
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -13,12 +16,77 @@ pub struct BorrowedRequest<'a> {
     pub data: &'a [u8],
 }
 
+/// Pn532 Request consisting of a [`Command`] and up to `N` bytes of extra command data
+///
+/// Used for commands whose data has a variable length.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct BoundedRequest<const N: usize> {
+    pub command: Command,
+    data: [u8; N],
+    len: usize,
+}
+
 impl<'a, const N: usize> From<&'a Request<N>> for BorrowedRequest<'a> {
     fn from(value: &'a Request<N>) -> BorrowedRequest<'a> {
         BorrowedRequest::new(value.command, &value.data)
     }
 }
 
+impl<'a, const N: usize> From<&'a BoundedRequest<N>> for BorrowedRequest<'a> {
+    fn from(value: &'a BoundedRequest<N>) -> BorrowedRequest<'a> {
+        BorrowedRequest::new(value.command, value.data())
+    }
+}
+
+/// A request that can be sent by a [`Pn532`](crate::Pn532) with an internal buffer of `N` bytes
+///
+/// For [`Request`] and [`BoundedRequest`] references it is checked at compile time that the request fits into the buffer:
+/// ```compile_fail
+/// # use pn532::doc_test_helper::get_pn532;
+/// use pn532::requests::Command;
+/// use pn532::Request;
+///
+/// let mut pn532 = get_pn532(); // 32 byte buffer
+/// pn532.send(&Request::new(Command::InDataExchange, [0; 24]));
+/// ```
+/// A [`BorrowedRequest`] is checked at runtime and is rejected with
+/// [`Error::RequestTooLarge`].
+pub trait IntoRequest<'a, const N: usize> {
+    fn into_request(self) -> BorrowedRequest<'a>;
+}
+
+impl<'a, const M: usize, const N: usize> IntoRequest<'a, N> for &'a Request<M> {
+    #[inline]
+    fn into_request(self) -> BorrowedRequest<'a> {
+        let () = AssertFits::<M, N>::OK;
+        self.into()
+    }
+}
+
+impl<'a, const M: usize, const N: usize> IntoRequest<'a, N> for &'a BoundedRequest<M> {
+    #[inline]
+    fn into_request(self) -> BorrowedRequest<'a> {
+        let () = AssertFits::<M, N>::OK;
+        self.into()
+    }
+}
+
+impl<'a, const N: usize> IntoRequest<'a, N> for BorrowedRequest<'a> {
+    #[inline]
+    fn into_request(self) -> BorrowedRequest<'a> {
+        self
+    }
+}
+
+struct AssertFits<const M: usize, const N: usize>;
+
+impl<const M: usize, const N: usize> AssertFits<M, N> {
+    const OK: () = assert!(
+        frame_size(M) <= N,
+        "the request does not fit into the buffer of the Pn532"
+    );
+}
+
 impl<const N: usize> Request<N> {
     #[inline]
     pub const fn new(command: Command, data: [u8; N]) -> Self {
@@ -33,10 +101,35 @@ impl<'a> BorrowedRequest<'a> {
     }
 }
 
+impl<const N: usize> BoundedRequest<N> {
+    /// Copies `data` into the request
+    ///
+    /// # Panics
+    /// if `data` is longer than `N` bytes
+    pub const fn new(command: Command, data: &[u8]) -> Self {
+        assert!(data.len() <= N, "the data does not fit into the request");
+        let mut request = BoundedRequest {
+            command,
+            data: [0; N],
+            len: 0,
+        };
+        while request.len < data.len() {
+            request.data[request.len] = data[request.len];
+            request.len += 1;
+        }
+        request
+    }
+
+    /// The extra command data
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
 impl Request<0> {
     pub const GET_FIRMWARE_VERSION: Request<0> = Request::new(Command::GetFirmwareVersion, []);
     pub const INLIST_ONE_ISO_A_TARGET: Request<2> =
-        Request::new(Command::InListPassiveTarget, [1, CardType::IsoTypeA as u8]);
+        Request::inlist_passive_targets(MaxTargets::One, CardType::IsoTypeA);
 
     pub const SELECT_TAG_1: Request<1> = Request::new(Command::InSelect, [1]);
     pub const SELECT_TAG_2: Request<1> = Request::new(Command::InSelect, [2]);
@@ -45,6 +138,13 @@ impl Request<0> {
     pub const RELEASE_TAG_1: Request<1> = Request::new(Command::InRelease, [1]);
     pub const RELEASE_TAG_2: Request<1> = Request::new(Command::InRelease, [2]);
 
+    pub const NTAG_GET_VERSION: Request<1> =
+        Request::new(Command::InCommunicateThru, [NTAGCommand::GetVersion as u8]);
+    pub const NTAG_READ_SIG: Request<2> = Request::new(
+        Command::InCommunicateThru,
+        [NTAGCommand::ReadSig as u8, 0x00],
+    );
+
     pub const fn sam_configuration(mode: SAMMode, use_irq_pin: bool) -> Request<3> {
         // TODO use_irq_pin seems to not have any effect
         let (mode, timeout) = match mode {
@@ -59,6 +159,116 @@ impl Request<0> {
         )
     }
 
+    /// InListPassiveTarget without initiator data,
+    /// e.g. for [`CardType::IsoTypeA`] and [`CardType::Jewel`]
+    ///
+    /// Decode the response with [`ListedTargets::targets`](crate::responses::ListedTargets::targets).
+    pub const fn inlist_passive_targets(max_tg: MaxTargets, card_type: CardType) -> Request<2> {
+        Request::new(
+            Command::InListPassiveTarget,
+            [max_tg as u8, card_type as u8],
+        )
+    }
+
+    /// InListPassiveTarget of the ISO/IEC 14443 Type A target with the 4, 7 or 10 byte `uid`,
+    /// which is used for the cascade selection
+    ///
+    /// Use [`try_inlist_iso_a_target_by_uid`](Request::try_inlist_iso_a_target_by_uid)
+    /// for UIDs that are not known at compile time.
+    ///
+    /// # Panics
+    /// if `uid` is longer than 10 bytes
+    pub const fn inlist_iso_a_target_by_uid(uid: &[u8]) -> BoundedRequest<12> {
+        match inlist_by_uid(uid) {
+            Some(request) => request,
+            None => panic!("the UID is longer than 10 bytes"),
+        }
+    }
+
+    /// Like [`inlist_iso_a_target_by_uid`](Request::inlist_iso_a_target_by_uid),
+    /// but fails with [`Error::InvalidArgument`] if `uid` is longer than 10 bytes
+    pub const fn try_inlist_iso_a_target_by_uid<E: Debug>(
+        uid: &[u8],
+    ) -> Result<BoundedRequest<12>, Error<E>> {
+        match inlist_by_uid(uid) {
+            Some(request) => Ok(request),
+            None => Err(Error::InvalidArgument),
+        }
+    }
+
+    /// InListPassiveTarget of FeliCa targets with a polling request for `system_code`
+    ///
+    /// `request_code` 0x01 requests the system code in the response,
+    /// `time_slots` is the number of time slots minus one.
+    pub const fn inlist_felica_targets(
+        max_tg: MaxTargets,
+        baud_rate: FeliCaBaudRate,
+        system_code: [u8; 2],
+        request_code: u8,
+        time_slots: u8,
+    ) -> Request<7> {
+        Request::new(
+            Command::InListPassiveTarget,
+            [
+                max_tg as u8,
+                baud_rate as u8,
+                0x00, // polling command code
+                system_code[0],
+                system_code[1],
+                request_code,
+                time_slots,
+            ],
+        )
+    }
+
+    /// InListPassiveTarget of ISO/IEC 14443 Type B targets with the application family identifier `afi`,
+    /// `0x00` selects all families
+    pub const fn inlist_iso_b_targets(max_tg: MaxTargets, afi: u8) -> Request<3> {
+        Request::new(
+            Command::InListPassiveTarget,
+            [max_tg as u8, CardType::IsoTypeB as u8, afi],
+        )
+    }
+
+    /// InListPassiveTarget of a Jewel tag, only one Jewel tag can be initialized at a time
+    pub const fn inlist_jewel_target() -> Request<2> {
+        Request::inlist_passive_targets(MaxTargets::One, CardType::Jewel)
+    }
+
+    /// InAutoPoll of up to 15 target types
+    ///
+    /// `poll_nr` is the number of polling cycles, `0xFF` polls endlessly,
+    /// `period` is the period between two polling cycles in units of 150 ms.
+    ///
+    /// Decode the response with [`AutoPollResponse`](crate::responses::AutoPollResponse).
+    /// Use [`try_in_auto_poll`](Request::try_in_auto_poll) for types that are not known at compile time.
+    ///
+    /// # Panics
+    /// if `types` is empty or contains more than 15 types
+    pub const fn in_auto_poll(
+        poll_nr: u8,
+        period: u8,
+        types: &[AutoPollType],
+    ) -> BoundedRequest<17> {
+        match auto_poll(poll_nr, period, types) {
+            Some(request) => request,
+            None => panic!("InAutoPoll requires 1 to 15 target types"),
+        }
+    }
+
+    /// Like [`in_auto_poll`](Request::in_auto_poll),
+    /// but fails with [`Error::InvalidArgument`] if `types` is empty or contains more than 15 types
+    pub const fn try_in_auto_poll<E: Debug>(
+        poll_nr: u8,
+        period: u8,
+        types: &[AutoPollType],
+    ) -> Result<BoundedRequest<17>, Error<E>> {
+        match auto_poll(poll_nr, period, types) {
+            Some(request) => Ok(request),
+            None => Err(Error::InvalidArgument),
+        }
+    }
+
     pub const fn rf_regulation_test(tx_speed: TxSpeed, tx_framing: TxFraming) -> Request<1> {
         Request::new(
             Command::RFRegulationTest,
@@ -86,6 +296,47 @@ impl Request<0> {
             ],
         )
     }
+    /// Reads the pages from `start_page` to `end_page` inclusive
+    pub const fn ntag_fast_read(start_page: u8, end_page: u8) -> Request<3> {
+        Request::new(
+            Command::InCommunicateThru,
+            [NTAGCommand::FastRead as u8, start_page, end_page],
+        )
+    }
+    /// Writes the first 4 of the 16 `bytes` to `page`
+    pub const fn ntag_comp_write(page: u8, bytes: &[u8; 16]) -> Request<19> {
+        Request::new(
+            Command::InDataExchange,
+            [
+                0x01,
+                NTAGCommand::CompWrite as u8,
+                page,
+                bytes[0],
+                bytes[1],
+                bytes[2],
+                bytes[3],
+                bytes[4],
+                bytes[5],
+                bytes[6],
+                bytes[7],
+                bytes[8],
+                bytes[9],
+                bytes[10],
+                bytes[11],
+                bytes[12],
+                bytes[13],
+                bytes[14],
+                bytes[15],
+            ],
+        )
+    }
+    /// Reads the 24 bit one-way `counter`, the NFC counter of an NTAG21x has the address 0x02
+    pub const fn ntag_read_cnt(counter: u8) -> Request<2> {
+        Request::new(
+            Command::InCommunicateThru,
+            [NTAGCommand::ReadCnt as u8, counter],
+        )
+    }
     pub const fn ntag_pwd_auth(bytes: &[u8; 4]) -> Request<5> {
         Request::new(
             Command::InCommunicateThru,
@@ -98,6 +349,88 @@ impl Request<0> {
             ],
         )
     }
+
+    pub const fn mifare_authenticate(
+        key_type: KeyType,
+        block: u8,
+        key: &[u8; 6],
+        uid: &[u8; 4],
+    ) -> Request<13> {
+        let command = match key_type {
+            KeyType::A => MifareCommand::AuthenticationWithKeyA,
+            KeyType::B => MifareCommand::AuthenticationWithKeyB,
+        };
+        Request::new(
+            Command::InDataExchange,
+            [
+                0x01,
+                command as u8,
+                block,
+                key[0],
+                key[1],
+                key[2],
+                key[3],
+                key[4],
+                key[5],
+                uid[0],
+                uid[1],
+                uid[2],
+                uid[3],
+            ],
+        )
+    }
+    pub const fn mifare_read(block: u8) -> Request<3> {
+        Request::new(
+            Command::InDataExchange,
+            [0x01, MifareCommand::Read as u8, block],
+        )
+    }
+    pub const fn mifare_write(block: u8, bytes: &[u8; 16]) -> Request<19> {
+        let mut data = [0; 19];
+        data[0] = 0x01;
+        data[1] = MifareCommand::Write as u8;
+        data[2] = block;
+        let mut i = 0;
+        while i < 16 {
+            data[3 + i] = bytes[i];
+            i += 1;
+        }
+        Request::new(Command::InDataExchange, data)
+    }
+    /// Adds `value` to the value block and stores the result in the transfer buffer
+    pub const fn mifare_increment(block: u8, value: u32) -> Request<7> {
+        Request::mifare_value_operation(MifareCommand::Increment, block, value)
+    }
+    /// Subtracts `value` from the value block and stores the result in the transfer buffer
+    pub const fn mifare_decrement(block: u8, value: u32) -> Request<7> {
+        Request::mifare_value_operation(MifareCommand::Decrement, block, value)
+    }
+    /// Copies the value block into the transfer buffer
+    pub const fn mifare_restore(block: u8) -> Request<7> {
+        Request::mifare_value_operation(MifareCommand::Restore, block, 0)
+    }
+    /// Writes the transfer buffer to the block
+    pub const fn mifare_transfer(block: u8) -> Request<3> {
+        Request::new(
+            Command::InDataExchange,
+            [0x01, MifareCommand::Transfer as u8, block],
+        )
+    }
+    const fn mifare_value_operation(command: MifareCommand, block: u8, value: u32) -> Request<7> {
+        let value = value.to_le_bytes();
+        Request::new(
+            Command::InDataExchange,
+            [
+                0x01,
+                command as u8,
+                block,
+                value[0],
+                value[1],
+                value[2],
+                value[3],
+            ],
+        )
+    }
 }
 
 /// Commands supported by the Pn532
@@ -288,6 +621,113 @@ pub enum CardType {
     Jewel = 0x04,
 }
 
+/// Maximum number of targets to be initialized by [`Command::InListPassiveTarget`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(u8)]
+pub enum MaxTargets {
+    One = 1,
+    Two = 2,
+}
+
+/// Baud rate of FeliCa targets to be used in [`Command::InListPassiveTarget`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(u8)]
+pub enum FeliCaBaudRate {
+    /// 212 kbps
+    FeliCa212kbps = CardType::FeliCa212kbps as u8,
+    /// 424 kbps
+    FeliCa424kbps = CardType::FeliCa424kbps as u8,
+}
+
+/// Target type to be used in [`Command::InAutoPoll`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(u8)]
+pub enum AutoPollType {
+    /// Generic passive 106 kbps (ISO/IEC14443-4A, MIFARE and DEP)
+    GenericPassive106kbps = 0x00,
+    /// Generic passive 212 kbps (FeliCa and DEP)
+    GenericPassive212kbps = 0x01,
+    /// Generic passive 424 kbps (FeliCa and DEP)
+    GenericPassive424kbps = 0x02,
+    /// Passive 106 kbps ISO/IEC14443-3B
+    IsoTypeB = 0x03,
+    /// Innovision Jewel tag
+    Jewel = 0x04,
+    /// MIFARE card
+    Mifare = 0x10,
+    /// FeliCa 212 kbps card
+    FeliCa212kbps = 0x11,
+    /// FeliCa 424 kbps card
+    FeliCa424kbps = 0x12,
+    /// Passive 106 kbps ISO/IEC14443-4A
+    IsoDepTypeA = 0x20,
+    /// Passive 106 kbps ISO/IEC14443-4B
+    IsoDepTypeB = 0x23,
+    /// DEP passive 106 kbps
+    DepPassive106kbps = 0x40,
+    /// DEP passive 212 kbps
+    DepPassive212kbps = 0x41,
+    /// DEP passive 424 kbps
+    DepPassive424kbps = 0x42,
+    /// DEP active 106 kbps
+    DepActive106kbps = 0x80,
+    /// DEP active 212 kbps
+    DepActive212kbps = 0x81,
+    /// DEP active 424 kbps
+    DepActive424kbps = 0x82,
+}
+
+impl AutoPollType {
+    /// Card type of the target data of a passive target, `None` for DEP targets
+    pub const fn card_type(self) -> Option<CardType> {
+        match self {
+            AutoPollType::GenericPassive106kbps
+            | AutoPollType::Mifare
+            | AutoPollType::IsoDepTypeA => Some(CardType::IsoTypeA),
+            AutoPollType::GenericPassive212kbps | AutoPollType::FeliCa212kbps => {
+                Some(CardType::FeliCa212kbps)
+            }
+            AutoPollType::GenericPassive424kbps | AutoPollType::FeliCa424kbps => {
+                Some(CardType::FeliCa424kbps)
+            }
+            AutoPollType::IsoTypeB | AutoPollType::IsoDepTypeB => Some(CardType::IsoTypeB),
+            AutoPollType::Jewel => Some(CardType::Jewel),
+            AutoPollType::DepPassive106kbps
+            | AutoPollType::DepPassive212kbps
+            | AutoPollType::DepPassive424kbps
+            | AutoPollType::DepActive106kbps
+            | AutoPollType::DepActive212kbps
+            | AutoPollType::DepActive424kbps => None,
+        }
+    }
+}
+
+impl TryFrom<u8> for AutoPollType {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<AutoPollType, ()> {
+        match value {
+            0x00 => Ok(AutoPollType::GenericPassive106kbps),
+            0x01 => Ok(AutoPollType::GenericPassive212kbps),
+            0x02 => Ok(AutoPollType::GenericPassive424kbps),
+            0x03 => Ok(AutoPollType::IsoTypeB),
+            0x04 => Ok(AutoPollType::Jewel),
+            0x10 => Ok(AutoPollType::Mifare),
+            0x11 => Ok(AutoPollType::FeliCa212kbps),
+            0x12 => Ok(AutoPollType::FeliCa424kbps),
+            0x20 => Ok(AutoPollType::IsoDepTypeA),
+            0x23 => Ok(AutoPollType::IsoDepTypeB),
+            0x40 => Ok(AutoPollType::DepPassive106kbps),
+            0x41 => Ok(AutoPollType::DepPassive212kbps),
+            0x42 => Ok(AutoPollType::DepPassive424kbps),
+            0x80 => Ok(AutoPollType::DepActive106kbps),
+            0x81 => Ok(AutoPollType::DepActive212kbps),
+            0x82 => Ok(AutoPollType::DepActive424kbps),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Bitrate to be used in [`Command::RFRegulationTest`]
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 #[repr(u8)]
@@ -337,3 +777,43 @@ pub enum MifareCommand {
     Restore = 0xC2,
     Transfer = 0xB0,
 }
+
+/// InListPassiveTarget of the ISO/IEC 14443 Type A target with `uid`, `None` if `uid` is longer than 10 bytes
+const fn inlist_by_uid(uid: &[u8]) -> Option<BoundedRequest<12>> {
+    if uid.len() > 10 {
+        return None;
+    }
+    let mut data = [0; 12];
+    data[0] = MaxTargets::One as u8;
+    data[1] = CardType::IsoTypeA as u8;
+    let mut i = 0;
+    while i < uid.len() {
+        data[2 + i] = uid[i];
+        i += 1;
+    }
+    Some(BoundedRequest {
+        command: Command::InListPassiveTarget,
+        data,
+        len: 2 + uid.len(),
+    })
+}
+
+/// InAutoPoll of `types`, `None` if `types` is empty or contains more than 15 types
+const fn auto_poll(poll_nr: u8, period: u8, types: &[AutoPollType]) -> Option<BoundedRequest<17>> {
+    if types.is_empty() || types.len() > 15 {
+        return None;
+    }
+    let mut data = [0; 17];
+    data[0] = poll_nr;
+    data[1] = period;
+    let mut i = 0;
+    while i < types.len() {
+        data[2 + i] = types[i] as u8;
+        i += 1;
+    }
+    Some(BoundedRequest {
+        command: Command::InAutoPoll,
+        data,
+        len: 2 + types.len(),
+    })
+}
@@ -0,0 +1,1067 @@
+//! Pn532 Responses
+//!
+//! Typed representations of the data returned for each [`Command`].
+//! Use [`Pn532::process_typed`](crate::Pn532::process_typed) to send a request and decode its response.
+use crate::requests::{AutoPollType, CardType, Command};
+use crate::ErrorCode;
+
+/// Could not decode the data of a response frame
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ResponseError {
+    /// The response data is shorter than the fields of the response
+    TooShort,
+    /// The response data is longer than the fields of the response
+    TooLong,
+    /// A field contains a value that is not described in the User Manual
+    InvalidValue,
+    /// The response carries a status byte with this error code
+    Status(u8),
+    /// The response type does not describe the response of the sent command
+    UnexpectedCommand,
+}
+
+/// Decoded response data
+///
+/// `parse` receives the response data without frame identifier and response code,
+/// i.e. exactly what [`Pn532::process`](crate::Pn532::process) returns.
+pub trait Response<'a>: Sized {
+    /// Returns `true` if the response of `command` can be decoded into `Self`
+    fn is_response_to(command: Command) -> bool;
+    /// Decodes the response data
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError>;
+}
+
+/// The raw response data, for any command
+impl<'a> Response<'a> for &'a [u8] {
+    fn is_response_to(_command: Command) -> bool {
+        true
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        Ok(data)
+    }
+}
+
+/// Commands without response data
+impl Response<'_> for () {
+    fn is_response_to(command: Command) -> bool {
+        matches!(
+            command,
+            Command::WriteRegister
+                | Command::WriteGPIO
+                | Command::SetSerialBaudRate
+                | Command::SetParameters
+                | Command::SAMConfiguration
+                | Command::RFConfiguration
+        )
+    }
+    fn parse(data: &[u8]) -> Result<Self, ResponseError> {
+        exact::<0>(data).map(|_| ())
+    }
+}
+
+/// Status byte returned by many commands
+///
+/// The lower six bits are zero on success, otherwise they contain an [`ErrorCode`].
+/// Bit 6 is the "More Information" bit and bit 7 the NAD bit.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Status(pub u8);
+
+impl Status {
+    /// Returns `true` if the status signals success
+    pub const fn is_ok(self) -> bool {
+        self.code() == 0
+    }
+    /// Error code without the MI and NAD bits
+    pub const fn code(self) -> u8 {
+        self.0 & 0b0011_1111
+    }
+    /// Returns the [`ErrorCode`] if the status does not signal success
+    ///
+    /// Unknown error codes are returned as `Err`.
+    pub fn error_code(self) -> Option<Result<ErrorCode, u8>> {
+        match self.code() {
+            0 => None,
+            code => Some(ErrorCode::try_from(code).map_err(|()| code)),
+        }
+    }
+    /// "More Information" bit: the data is chained and more data has to be exchanged
+    pub const fn more_information(self) -> bool {
+        self.0 & 0b0100_0000 != 0
+    }
+    /// NAD bit: the data is preceded by a NAD byte
+    pub const fn nad_present(self) -> bool {
+        self.0 & 0b1000_0000 != 0
+    }
+}
+
+impl Response<'_> for Status {
+    fn is_response_to(command: Command) -> bool {
+        matches!(
+            command,
+            Command::PowerDown
+                | Command::InPSL
+                | Command::InDeselect
+                | Command::InRelease
+                | Command::InSelect
+                | Command::TgSetGeneralBytes
+                | Command::TgSetData
+                | Command::TgSetMetaData
+                | Command::TgResponseToInitiator
+        )
+    }
+    fn parse(data: &[u8]) -> Result<Self, ResponseError> {
+        let [status] = exact(data)?;
+        Ok(Status(status))
+    }
+}
+
+/// Response data of a command whose response starts with a status byte,
+/// see [`Pn532::process_status`](crate::Pn532::process_status)
+///
+/// Decoding fails with [`ResponseError::Status`] if the status byte contains an error code.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct StatusResponse<'a> {
+    /// "More Information" bit: the data is chained and more data has to be exchanged
+    pub more_information: bool,
+    /// NAD bit: the data is preceded by a NAD byte
+    pub nad_present: bool,
+    /// Response data without the status byte
+    pub data: &'a [u8],
+}
+
+impl<'a> Response<'a> for StatusResponse<'a> {
+    fn is_response_to(command: Command) -> bool {
+        Status::is_response_to(command)
+            || DataIn::is_response_to(command)
+            || matches!(
+                command,
+                Command::InJumpForDEP | Command::InJumpForPSL | Command::InATR
+            )
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        let [status, data @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        let status = Status(*status);
+        if !status.is_ok() {
+            return Err(ResponseError::Status(status.code()));
+        }
+        Ok(StatusResponse {
+            more_information: status.more_information(),
+            nad_present: status.nad_present(),
+            data,
+        })
+    }
+}
+
+/// Response to [`Command::GetFirmwareVersion`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct FirmwareVersion {
+    /// Version of the IC, `0x32` for the PN532
+    pub ic: u8,
+    /// Version of the firmware
+    pub version: u8,
+    /// Revision of the firmware
+    pub revision: u8,
+    /// Bitmask of the supported protocols
+    pub support: u8,
+}
+
+impl FirmwareVersion {
+    /// ISO/IEC 14443 Type A is supported
+    pub const fn supports_iso_a(&self) -> bool {
+        self.support & 0b001 != 0
+    }
+    /// ISO/IEC 14443 Type B is supported
+    pub const fn supports_iso_b(&self) -> bool {
+        self.support & 0b010 != 0
+    }
+    /// ISO 18092 is supported
+    pub const fn supports_iso_18092(&self) -> bool {
+        self.support & 0b100 != 0
+    }
+}
+
+impl Response<'_> for FirmwareVersion {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::GetFirmwareVersion
+    }
+    fn parse(data: &[u8]) -> Result<Self, ResponseError> {
+        let [ic, version, revision, support] = exact(data)?;
+        Ok(FirmwareVersion {
+            ic,
+            version,
+            revision,
+            support,
+        })
+    }
+}
+
+/// Target entry of [`GeneralStatus`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct ActiveTarget {
+    /// Logical number of the target
+    pub tg: u8,
+    /// Bit rate in reception
+    pub br_rx: u8,
+    /// Bit rate in transmission
+    pub br_tx: u8,
+    /// Modulation type
+    pub modulation_type: u8,
+}
+
+/// Response to [`Command::GetGeneralStatus`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct GeneralStatus {
+    /// Status of the last command
+    pub last_error: Status,
+    /// `true` if an external RF field is present and detected
+    pub field: bool,
+    target_count: usize,
+    targets: [ActiveTarget; 2],
+    /// Status of the SAM
+    pub sam_status: u8,
+}
+
+impl GeneralStatus {
+    /// Targets currently handled by the PN532 as initiator
+    pub fn targets(&self) -> &[ActiveTarget] {
+        &self.targets[..self.target_count]
+    }
+}
+
+impl Response<'_> for GeneralStatus {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::GetGeneralStatus
+    }
+    fn parse(data: &[u8]) -> Result<Self, ResponseError> {
+        let [err, field, nb_tg, rest @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        let target_count = *nb_tg as usize;
+        if target_count > 2 {
+            return Err(ResponseError::InvalidValue);
+        }
+        let (target_data, sam_status) = split(rest, 4 * target_count)?;
+        let [sam_status] = exact(sam_status)?;
+        let mut targets = [ActiveTarget::default(); 2];
+        for (target, chunk) in targets.iter_mut().zip(target_data.chunks_exact(4)) {
+            *target = ActiveTarget {
+                tg: chunk[0],
+                br_rx: chunk[1],
+                br_tx: chunk[2],
+                modulation_type: chunk[3],
+            };
+        }
+        Ok(GeneralStatus {
+            last_error: Status(*err),
+            field: *field == 0x01,
+            target_count,
+            targets,
+            sam_status,
+        })
+    }
+}
+
+/// Response to [`Command::ReadRegister`]: the register values in the requested order
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct RegisterValues<'a>(pub &'a [u8]);
+
+impl<'a> Response<'a> for RegisterValues<'a> {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::ReadRegister
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        Ok(RegisterValues(data))
+    }
+}
+
+/// Response to [`Command::ReadGPIO`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct GpioState {
+    /// State of the P3 port
+    pub p3: u8,
+    /// State of the P7 port
+    pub p7: u8,
+    /// State of the I0 and I1 pins
+    pub i0i1: u8,
+}
+
+impl Response<'_> for GpioState {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::ReadGPIO
+    }
+    fn parse(data: &[u8]) -> Result<Self, ResponseError> {
+        let [p3, p7, i0i1] = exact(data)?;
+        Ok(GpioState { p3, p7, i0i1 })
+    }
+}
+
+/// ATR_RES of a DEP target
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AtrRes<'a> {
+    /// NFCID3 of the target
+    pub nfcid3: [u8; 10],
+    /// Device ID
+    pub did: u8,
+    /// Supported send bit rates
+    pub bs: u8,
+    /// Supported receive bit rates
+    pub br: u8,
+    /// Timeout value
+    pub to: u8,
+    /// Optional parameters
+    pub pp: u8,
+    /// General bytes
+    pub general_bytes: &'a [u8],
+}
+
+impl<'a> AtrRes<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        let (nfcid3, rest) = split(data, 10)?;
+        let [did, bs, br, to, pp, general_bytes @ ..] = rest else {
+            return Err(ResponseError::TooShort);
+        };
+        Ok(AtrRes {
+            nfcid3: nfcid3.try_into().unwrap(),
+            did: *did,
+            bs: *bs,
+            br: *br,
+            to: *to,
+            pp: *pp,
+            general_bytes,
+        })
+    }
+}
+
+/// Response to [`Command::InJumpForDEP`] and [`Command::InJumpForPSL`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct JumpResponse<'a> {
+    /// Logical number of the activated target
+    pub tg: u8,
+    /// ATR_RES of the activated target
+    pub atr_res: AtrRes<'a>,
+}
+
+impl<'a> Response<'a> for JumpResponse<'a> {
+    fn is_response_to(command: Command) -> bool {
+        matches!(command, Command::InJumpForDEP | Command::InJumpForPSL)
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        let [tg, rest @ ..] = ok_status(data)? else {
+            return Err(ResponseError::TooShort);
+        };
+        Ok(JumpResponse {
+            tg: *tg,
+            atr_res: AtrRes::parse(rest)?,
+        })
+    }
+}
+
+/// Response to [`Command::InATR`]
+impl<'a> Response<'a> for AtrRes<'a> {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::InATR
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        AtrRes::parse(ok_status(data)?)
+    }
+}
+
+/// Response to [`Command::InListPassiveTarget`]
+///
+/// The format of the target data depends on the card type of the request.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ListedTargets<'a> {
+    /// Number of initialized targets
+    pub count: u8,
+    /// Target data of all initialized targets
+    pub data: &'a [u8],
+}
+
+impl<'a> ListedTargets<'a> {
+    /// Decodes the target data of a request with [`CardType::IsoTypeA`]
+    pub fn iso_a_targets(&self) -> IsoATargets<'a> {
+        IsoATargets {
+            remaining: self.count,
+            data: self.data,
+        }
+    }
+
+    /// Decodes the target data of a request with `card_type`
+    pub fn targets(&self, card_type: CardType) -> Targets<'a> {
+        Targets {
+            card_type,
+            remaining: self.count,
+            data: self.data,
+        }
+    }
+}
+
+impl<'a> Response<'a> for ListedTargets<'a> {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::InListPassiveTarget
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        let [count, data @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        if *count > 2 {
+            return Err(ResponseError::InvalidValue);
+        }
+        Ok(ListedTargets {
+            count: *count,
+            data,
+        })
+    }
+}
+
+/// ISO/IEC 14443 Type A target
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct IsoATarget<'a> {
+    /// Logical number of the target
+    pub tg: u8,
+    /// SENS_RES (ATQA)
+    pub sens_res: [u8; 2],
+    /// SEL_RES (SAK)
+    pub sel_res: u8,
+    /// NFCID1 (UID)
+    pub uid: &'a [u8],
+    /// ATS including its length byte, if the target is ISO/IEC 14443-4 compliant
+    pub ats: Option<&'a [u8]>,
+}
+
+/// Iterator over the [`IsoATarget`]s of [`ListedTargets`]
+#[derive(Clone, Debug)]
+pub struct IsoATargets<'a> {
+    remaining: u8,
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for IsoATargets<'a> {
+    type Item = Result<IsoATarget<'a>, ResponseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let result = IsoATarget::parse(self.data).map(|(target, rest)| {
+            self.data = rest;
+            target
+        });
+        if result.is_err() {
+            self.remaining = 0;
+        }
+        Some(result)
+    }
+}
+
+impl<'a> IsoATarget<'a> {
+    fn parse(data: &'a [u8]) -> Result<(Self, &'a [u8]), ResponseError> {
+        let [tg, sens_res_0, sens_res_1, sel_res, uid_len, rest @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        let (uid, mut rest) = split(rest, *uid_len as usize)?;
+        let mut ats = None;
+        // bit 6 of SEL_RES: compliant with ISO/IEC 14443-4
+        if sel_res & 0x20 != 0 {
+            let ats_len = *rest.first().ok_or(ResponseError::TooShort)? as usize;
+            let (ats_bytes, after_ats) = split(rest, ats_len)?;
+            ats = Some(ats_bytes);
+            rest = after_ats;
+        }
+        let target = IsoATarget {
+            tg: *tg,
+            sens_res: [*sens_res_0, *sens_res_1],
+            sel_res: *sel_res,
+            uid,
+            ats,
+        };
+        Ok((target, rest))
+    }
+}
+
+/// FeliCa target
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct FeliCaTarget {
+    /// Logical number of the target
+    pub tg: u8,
+    /// Response code of POL_RES, `0x01`
+    pub response_code: u8,
+    /// IDm (NFCID2)
+    pub idm: [u8; 8],
+    /// PMm (Pad)
+    pub pmm: [u8; 8],
+    /// System code, if it was requested with request code `0x01`
+    pub system_code: Option<[u8; 2]>,
+}
+
+impl FeliCaTarget {
+    fn parse(data: &[u8]) -> Result<(Self, &[u8]), ResponseError> {
+        let [tg, pol_res_len, rest @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        // the length of POL_RES includes the length byte
+        let len = (*pol_res_len as usize)
+            .checked_sub(1)
+            .ok_or(ResponseError::InvalidValue)?;
+        let (pol_res, rest) = split(rest, len)?;
+        let (response_code, ids, system_code) = match pol_res {
+            [response_code, ids @ ..] if ids.len() == 16 => (*response_code, ids, None),
+            [response_code, ids @ .., sc0, sc1] if ids.len() == 16 => {
+                (*response_code, ids, Some([*sc0, *sc1]))
+            }
+            _ if pol_res.len() < 17 => return Err(ResponseError::TooShort),
+            _ => return Err(ResponseError::TooLong),
+        };
+        let (idm, pmm) = ids.split_at(8);
+        let target = FeliCaTarget {
+            tg: *tg,
+            response_code,
+            idm: idm.try_into().unwrap(),
+            pmm: pmm.try_into().unwrap(),
+            system_code,
+        };
+        Ok((target, rest))
+    }
+}
+
+/// ISO/IEC 14443 Type B target
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct IsoBTarget<'a> {
+    /// Logical number of the target
+    pub tg: u8,
+    /// ATQB
+    pub atqb: [u8; 12],
+    /// ATTRIB_RES without its length byte
+    pub attrib_res: &'a [u8],
+}
+
+impl<'a> IsoBTarget<'a> {
+    fn parse(data: &'a [u8]) -> Result<(Self, &'a [u8]), ResponseError> {
+        let [tg, rest @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        let (atqb, rest) = split(rest, 12)?;
+        let [attrib_res_len, rest @ ..] = rest else {
+            return Err(ResponseError::TooShort);
+        };
+        let (attrib_res, rest) = split(rest, *attrib_res_len as usize)?;
+        let target = IsoBTarget {
+            tg: *tg,
+            atqb: atqb.try_into().unwrap(),
+            attrib_res,
+        };
+        Ok((target, rest))
+    }
+}
+
+/// Innovision Jewel tag
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct JewelTarget {
+    /// Logical number of the target
+    pub tg: u8,
+    /// SENS_RES (ATQA)
+    pub sens_res: [u8; 2],
+    /// Jewel ID
+    pub jewel_id: [u8; 4],
+}
+
+impl JewelTarget {
+    fn parse(data: &[u8]) -> Result<(Self, &[u8]), ResponseError> {
+        let [tg, sens_res_0, sens_res_1, id0, id1, id2, id3, rest @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        let target = JewelTarget {
+            tg: *tg,
+            sens_res: [*sens_res_0, *sens_res_1],
+            jewel_id: [*id0, *id1, *id2, *id3],
+        };
+        Ok((target, rest))
+    }
+}
+
+/// Target initialized by [`Command::InListPassiveTarget`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Target<'a> {
+    IsoA(IsoATarget<'a>),
+    FeliCa(FeliCaTarget),
+    IsoB(IsoBTarget<'a>),
+    Jewel(JewelTarget),
+}
+
+impl<'a> Target<'a> {
+    /// Logical number of the target
+    pub const fn tg(&self) -> u8 {
+        match self {
+            Target::IsoA(target) => target.tg,
+            Target::FeliCa(target) => target.tg,
+            Target::IsoB(target) => target.tg,
+            Target::Jewel(target) => target.tg,
+        }
+    }
+
+    fn parse(card_type: CardType, data: &'a [u8]) -> Result<(Self, &'a [u8]), ResponseError> {
+        Ok(match card_type {
+            CardType::IsoTypeA => {
+                let (target, rest) = IsoATarget::parse(data)?;
+                (Target::IsoA(target), rest)
+            }
+            CardType::FeliCa212kbps | CardType::FeliCa424kbps => {
+                let (target, rest) = FeliCaTarget::parse(data)?;
+                (Target::FeliCa(target), rest)
+            }
+            CardType::IsoTypeB => {
+                let (target, rest) = IsoBTarget::parse(data)?;
+                (Target::IsoB(target), rest)
+            }
+            CardType::Jewel => {
+                let (target, rest) = JewelTarget::parse(data)?;
+                (Target::Jewel(target), rest)
+            }
+        })
+    }
+}
+
+/// Iterator over the [`Target`]s of [`ListedTargets`]
+#[derive(Clone, Debug)]
+pub struct Targets<'a> {
+    card_type: CardType,
+    remaining: u8,
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Targets<'a> {
+    type Item = Result<Target<'a>, ResponseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let result = Target::parse(self.card_type, self.data).map(|(target, rest)| {
+            self.data = rest;
+            target
+        });
+        if result.is_err() {
+            self.remaining = 0;
+        }
+        Some(result)
+    }
+}
+
+/// Response to [`Command::InAutoPoll`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AutoPollResponse<'a> {
+    /// Number of found targets
+    pub count: u8,
+    /// Type, length and target data of all found targets
+    pub data: &'a [u8],
+}
+
+impl<'a> AutoPollResponse<'a> {
+    /// Iterates over the found targets
+    pub fn targets(&self) -> PolledTargets<'a> {
+        PolledTargets {
+            remaining: self.count,
+            data: self.data,
+        }
+    }
+}
+
+impl<'a> Response<'a> for AutoPollResponse<'a> {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::InAutoPoll
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        let [count, data @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        if *count > 2 {
+            return Err(ResponseError::InvalidValue);
+        }
+        Ok(AutoPollResponse {
+            count: *count,
+            data,
+        })
+    }
+}
+
+/// Target found by [`Command::InAutoPoll`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PolledTarget<'a> {
+    /// Type of the target
+    pub target_type: AutoPollType,
+    /// Target data in the format of InListPassiveTarget or, for DEP targets, of InJumpForDEP
+    pub target_data: &'a [u8],
+}
+
+/// Decoded target data of a [`PolledTarget`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PolledTargetData<'a> {
+    /// Passive target as returned by InListPassiveTarget
+    Passive(Target<'a>),
+    /// DEP target with its logical number and ATR_RES
+    Dep(JumpResponse<'a>),
+}
+
+impl<'a> PolledTarget<'a> {
+    /// Decodes the target data according to the target type
+    pub fn decode(&self) -> Result<PolledTargetData<'a>, ResponseError> {
+        match self.target_type.card_type() {
+            Some(card_type) => {
+                let (target, rest) = Target::parse(card_type, self.target_data)?;
+                if !rest.is_empty() {
+                    return Err(ResponseError::TooLong);
+                }
+                Ok(PolledTargetData::Passive(target))
+            }
+            None => {
+                let [tg, atr_res @ ..] = self.target_data else {
+                    return Err(ResponseError::TooShort);
+                };
+                Ok(PolledTargetData::Dep(JumpResponse {
+                    tg: *tg,
+                    atr_res: AtrRes::parse(atr_res)?,
+                }))
+            }
+        }
+    }
+}
+
+/// Iterator over the [`PolledTarget`]s of an [`AutoPollResponse`]
+#[derive(Clone, Debug)]
+pub struct PolledTargets<'a> {
+    remaining: u8,
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for PolledTargets<'a> {
+    type Item = Result<PolledTarget<'a>, ResponseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let result = match self.data {
+            [target_type, len, rest @ ..] => AutoPollType::try_from(*target_type)
+                .map_err(|()| ResponseError::InvalidValue)
+                .and_then(|target_type| {
+                    let (target_data, rest) = split(rest, *len as usize)?;
+                    self.data = rest;
+                    Ok(PolledTarget {
+                        target_type,
+                        target_data,
+                    })
+                }),
+            _ => Err(ResponseError::TooShort),
+        };
+        if result.is_err() {
+            self.remaining = 0;
+        }
+        Some(result)
+    }
+}
+
+/// Response to [`Command::InDataExchange`], [`Command::InCommunicateThru`],
+/// [`Command::TgGetData`] and [`Command::TgGetInitiatorCommand`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct DataIn<'a> {
+    /// Status of the exchange
+    pub status: Status,
+    /// Data received from the target or initiator
+    pub data: &'a [u8],
+}
+
+impl<'a> Response<'a> for DataIn<'a> {
+    fn is_response_to(command: Command) -> bool {
+        matches!(
+            command,
+            Command::InDataExchange
+                | Command::InCommunicateThru
+                | Command::TgGetData
+                | Command::TgGetInitiatorCommand
+        )
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        let [status, data @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        Ok(DataIn {
+            status: Status(*status),
+            data,
+        })
+    }
+}
+
+/// Response to [`Command::TgInitAsTarget`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TargetActivation<'a> {
+    /// Baud rate and mode of the activation
+    pub mode: u8,
+    /// First frame received from the initiator
+    pub initiator_command: &'a [u8],
+}
+
+impl<'a> Response<'a> for TargetActivation<'a> {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::TgInitAsTarget
+    }
+    fn parse(data: &'a [u8]) -> Result<Self, ResponseError> {
+        let [mode, initiator_command @ ..] = data else {
+            return Err(ResponseError::TooShort);
+        };
+        Ok(TargetActivation {
+            mode: *mode,
+            initiator_command,
+        })
+    }
+}
+
+/// Response to [`Command::TgGetTargetStatus`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TargetStatus {
+    /// State of the PN532 acting as target
+    pub state: u8,
+    /// Bit rate of the initiator (upper nibble) and of the target (lower nibble)
+    pub br_it: u8,
+}
+
+impl Response<'_> for TargetStatus {
+    fn is_response_to(command: Command) -> bool {
+        command == Command::TgGetTargetStatus
+    }
+    fn parse(data: &[u8]) -> Result<Self, ResponseError> {
+        let [state, br_it] = exact(data)?;
+        Ok(TargetStatus { state, br_it })
+    }
+}
+
+/// Returns `data` if it has exactly `N` bytes
+pub(crate) fn exact<const N: usize>(data: &[u8]) -> Result<[u8; N], ResponseError> {
+    match data.len() {
+        len if len < N => Err(ResponseError::TooShort),
+        len if len > N => Err(ResponseError::TooLong),
+        _ => Ok(data.try_into().unwrap()),
+    }
+}
+
+fn split(data: &[u8], mid: usize) -> Result<(&[u8], &[u8]), ResponseError> {
+    if data.len() < mid {
+        Err(ResponseError::TooShort)
+    } else {
+        Ok(data.split_at(mid))
+    }
+}
+
+/// Strips a leading status byte that has to signal success
+fn ok_status(data: &[u8]) -> Result<&[u8], ResponseError> {
+    StatusResponse::parse(data).map(|response| response.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Request;
+
+    #[test]
+    fn test_firmware_version() {
+        let version = FirmwareVersion::parse(&[0x32, 0x01, 0x06, 0x07]).unwrap();
+        assert_eq!(version.ic, 0x32);
+        assert!(version.supports_iso_a() && version.supports_iso_b());
+        assert!(version.supports_iso_18092());
+        assert_eq!(
+            FirmwareVersion::parse(&[0x32, 0x01, 0x06]),
+            Err(ResponseError::TooShort)
+        );
+    }
+
+    #[test]
+    fn test_general_status() {
+        let status =
+            GeneralStatus::parse(&[0x00, 0x01, 0x01, 0x01, 0x00, 0x00, 0x10, 0x80]).unwrap();
+        assert!(status.last_error.is_ok());
+        assert!(status.field);
+        assert_eq!(
+            status.targets(),
+            [ActiveTarget {
+                tg: 1,
+                br_rx: 0,
+                br_tx: 0,
+                modulation_type: 0x10
+            }]
+        );
+        assert_eq!(status.sam_status, 0x80);
+    }
+
+    #[test]
+    fn test_iso_a_targets() {
+        let data = [
+            0x01, // NbTg
+            0x01, 0x00, 0x44, 0x00, 0x07, 0x04, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC,
+        ];
+        let listed = ListedTargets::parse(&data).unwrap();
+        let mut targets = listed.iso_a_targets();
+        let target = targets.next().unwrap().unwrap();
+        assert_eq!(target.tg, 1);
+        assert_eq!(target.sens_res, [0x00, 0x44]);
+        assert_eq!(target.uid, [0x04, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]);
+        assert_eq!(target.ats, None);
+        assert!(targets.next().is_none());
+    }
+
+    #[test]
+    fn test_iso_a_target_with_ats() {
+        let data = [
+            0x01, 0x01, 0x03, 0x44, 0x20, 0x04, 0x01, 0x02, 0x03, 0x04, // UID
+            0x05, 0x75, 0x77, 0x81, 0x02, // ATS
+        ];
+        let listed = ListedTargets::parse(&data).unwrap();
+        let target = listed.iso_a_targets().next().unwrap().unwrap();
+        assert_eq!(target.ats, Some(&[0x05, 0x75, 0x77, 0x81, 0x02][..]));
+    }
+
+    #[test]
+    fn test_truncated_iso_a_target() {
+        let listed = ListedTargets::parse(&[0x01, 0x01, 0x00, 0x44, 0x00, 0x07, 0x04]).unwrap();
+        let mut targets = listed.iso_a_targets();
+        assert_eq!(targets.next(), Some(Err(ResponseError::TooShort)));
+        assert_eq!(targets.next(), None);
+    }
+
+    #[test]
+    fn test_felica_targets() {
+        let data = [
+            0x02, // NbTg
+            0x01, 0x12, 0x01, 0x01, 0x2E, 0x3D, 0x4C, 0x5B, 0x6A, 0x79, 0x88, // IDm
+            0x03, 0x01, 0x4B, 0x02, 0x4F, 0x49, 0x93, 0xFF, // PMm
+            0x02, 0x14, 0x01, 0x01, 0x2E, 0x3D, 0x4C, 0x5B, 0x6A, 0x79, 0x89, // IDm
+            0x03, 0x01, 0x4B, 0x02, 0x4F, 0x49, 0x93, 0xFF, 0x12, 0xFC, // system code
+        ];
+        let listed = ListedTargets::parse(&data).unwrap();
+        let mut targets = listed.targets(CardType::FeliCa212kbps);
+        let Some(Ok(Target::FeliCa(first))) = targets.next() else {
+            panic!("expected a FeliCa target");
+        };
+        assert_eq!(first.idm[7], 0x88);
+        assert_eq!(first.pmm[0], 0x03);
+        assert_eq!(first.system_code, None);
+        let second = targets.next().unwrap().unwrap();
+        assert_eq!(second.tg(), 2);
+        assert!(matches!(
+            second,
+            Target::FeliCa(FeliCaTarget {
+                system_code: Some([0x12, 0xFC]),
+                ..
+            })
+        ));
+        assert!(targets.next().is_none());
+    }
+
+    #[test]
+    fn test_iso_b_and_jewel_targets() {
+        let data = [
+            0x01, 0x01, // NbTg, Tg
+            0x50, 0x01, 0x02, 0x03, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x71, 0x71, // ATQB
+            0x01, 0x00, // ATTRIB_RES
+        ];
+        let listed = ListedTargets::parse(&data).unwrap();
+        let target = listed.targets(CardType::IsoTypeB).next().unwrap().unwrap();
+        let Target::IsoB(target) = target else {
+            panic!("expected a Type B target");
+        };
+        assert_eq!(target.atqb[1..5], [0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(target.attrib_res, [0x00]);
+
+        let data = [0x01, 0x01, 0x0C, 0x00, 0xB2, 0x56, 0x1A, 0x00];
+        let listed = ListedTargets::parse(&data).unwrap();
+        let target = listed.targets(CardType::Jewel).next().unwrap();
+        assert_eq!(
+            target,
+            Ok(Target::Jewel(JewelTarget {
+                tg: 1,
+                sens_res: [0x0C, 0x00],
+                jewel_id: [0xB2, 0x56, 0x1A, 0x00],
+            }))
+        );
+        let listed = ListedTargets::parse(&data[..5]).unwrap();
+        let target = listed.targets(CardType::Jewel).next().unwrap();
+        assert_eq!(target, Err(ResponseError::TooShort));
+    }
+
+    #[test]
+    fn test_auto_poll() {
+        let types = [AutoPollType::Mifare, AutoPollType::DepPassive106kbps];
+        let request = Request::in_auto_poll(0xFF, 0x02, &types);
+        assert_eq!(request.command, Command::InAutoPoll);
+        assert_eq!(request.data(), [0xFF, 0x02, 0x10, 0x40]);
+        assert_eq!(
+            Request::try_in_auto_poll::<()>(0xFF, 0x02, &[]),
+            Err(crate::Error::InvalidArgument)
+        );
+
+        let data = [
+            0x02, // NbTg
+            0x10, 0x09, 0x01, 0x00, 0x04, 0x08, 0x04, 0x12, 0x34, 0x56, 0x78, // MIFARE
+            0x40, 0x10, 0x01, // DEP
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, // NFCID3
+            0x00, 0x00, 0x00, 0x0E, 0x00, // DID, BS, BR, TO, PP
+        ];
+        let response = AutoPollResponse::parse(&data).unwrap();
+        let mut targets = response.targets();
+        let mifare = targets.next().unwrap().unwrap();
+        assert_eq!(mifare.target_type, AutoPollType::Mifare);
+        let Ok(PolledTargetData::Passive(Target::IsoA(target))) = mifare.decode() else {
+            panic!("expected a Type A target");
+        };
+        assert_eq!(target.uid, [0x12, 0x34, 0x56, 0x78]);
+        let dep = targets.next().unwrap().unwrap();
+        assert_eq!(dep.target_type, AutoPollType::DepPassive106kbps);
+        let Ok(PolledTargetData::Dep(jump)) = dep.decode() else {
+            panic!("expected a DEP target");
+        };
+        assert_eq!(jump.atr_res.to, 0x0E);
+        assert!(targets.next().is_none());
+
+        let response = AutoPollResponse::parse(&[0x01, 0x05, 0x00]).unwrap();
+        let mut targets = response.targets();
+        assert_eq!(targets.next(), Some(Err(ResponseError::InvalidValue)));
+        assert_eq!(targets.next(), None);
+    }
+
+    #[test]
+    fn test_status_responses() {
+        assert_eq!(
+            JumpResponse::parse(&[0x01]),
+            Err(ResponseError::Status(0x01))
+        );
+        let data_in = DataIn::parse(&[0x00, 0xAA, 0xBB]).unwrap();
+        assert!(data_in.status.is_ok());
+        assert_eq!(data_in.data, [0xAA, 0xBB]);
+        assert_eq!(<()>::parse(&[0x00]), Err(ResponseError::TooLong));
+    }
+
+    #[test]
+    fn test_status_bits() {
+        let status = Status(0x54);
+        assert!(!status.is_ok());
+        assert!(status.more_information());
+        assert!(!status.nad_present());
+        assert_eq!(
+            status.error_code(),
+            Some(Ok(ErrorCode::AuthenticationError))
+        );
+        assert_eq!(Status(0x3F).error_code(), Some(Err(0x3F)));
+        assert_eq!(Status(0x40).error_code(), None);
+
+        let response = StatusResponse::parse(&[0x40, 0xAA]).unwrap();
+        assert!(response.more_information);
+        assert_eq!(response.data, [0xAA]);
+        assert_eq!(
+            StatusResponse::parse(&[0x94]),
+            Err(ResponseError::Status(0x14))
+        );
+    }
+}
@@ -5,7 +5,7 @@ use core::task::Poll;
 use std::io::Write;
 use std::time::{Duration, Instant};
 
-use crate::protocol::CountDown;
+use crate::protocol::{continue_frame_in_chunks, read_frame_in_chunks, CountDown};
 use serialport::SerialPort;
 
 use crate::Interface;
@@ -33,6 +33,14 @@ impl Interface for SerialPortInterface {
     fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
         self.port.read_exact(buf)
     }
+
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_frame_in_chunks(self, buf)
+    }
+
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        continue_frame_in_chunks(self, buf, len)
+    }
 }
 
 impl SerialPortInterface {
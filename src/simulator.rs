@@ -0,0 +1,517 @@
+//! Simulated PN532 for testing without hardware
+//!
+//! [`SimulatedPn532`] implements [`Interface`] and answers the frames written by the host controller
+//! like a PN532 would: information frames with valid checksums are acknowledged with an ACK frame
+//! followed by a response frame or a syntax error frame,
+//! frames with invalid checksums are ignored,
+//! an ACK frame aborts the current command and a NACK frame repeats the last response frame.
+//!
+//! Supported commands are GetFirmwareVersion, SAMConfiguration, RFConfiguration,
+//! InListPassiveTarget, InDataExchange and InCommunicateThru.
+//! All other commands are answered with a syntax error frame.
+//! Contactless targets are simulated by [`VirtualTarget`]s,
+//! models of common tags are provided in [`tags`].
+//!
+//! ```
+//! # use pn532::doc_test_helper::NoOpTimer;
+//! use pn532::simulator::SimulatedPn532;
+//! use pn532::{Pn532, Request};
+//! use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+//!
+//! let mut pn532: Pn532<_, _, 32> = Pn532::new(SimulatedPn532::new(), NoOpTimer);
+//! let version = pn532.process(&Request::GET_FIRMWARE_VERSION, 4, 50.ms()).unwrap();
+//! assert_eq!(version, [0x32, 0x01, 0x06, 0x07]);
+//! ```
+use crate::protocol::{continue_frame_in_chunks, read_frame_in_chunks, ACK};
+use crate::requests::{CardType, Command};
+use crate::{ErrorCode, Interface};
+use core::cell::RefCell;
+use core::convert::Infallible;
+use core::task::Poll;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+pub mod tags;
+
+const HOST_TO_PN532: u8 = 0xD4;
+const PN532_TO_HOST: u8 = 0xD5;
+const ERROR_FRAME: [u8; 8] = [0x00, 0x00, 0xFF, 0x01, 0xFF, 0x7F, 0x81, 0x00];
+
+/// A contactless target in the field of a [`SimulatedPn532`]
+pub trait VirtualTarget {
+    /// Baud rate and modulation type the target answers to in InListPassiveTarget
+    fn card_type(&self) -> CardType;
+    /// Target data of the InListPassiveTarget response following the logical target number,
+    /// e.g. SENS_RES, SEL_RES, NFCID1 length, NFCID1 and ATS of a Type A target
+    fn target_data(&self) -> Vec<u8>;
+    /// Called when the target is activated by InListPassiveTarget.
+    /// Returns `false` if the target does not answer.
+    fn activate(&mut self) -> bool {
+        true
+    }
+    /// Handles the data of an InDataExchange and returns the data sent back by the target
+    /// or the error code for the status byte
+    fn exchange(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode>;
+    /// Handles the data of an InCommunicateThru, which is passed to [`exchange`](VirtualTarget::exchange)
+    /// by default
+    fn communicate_thru(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        self.exchange(data)
+    }
+}
+
+/// Shared targets can still be inspected by the test after they were added to the simulator
+impl<T: VirtualTarget> VirtualTarget for Rc<RefCell<T>> {
+    fn card_type(&self) -> CardType {
+        self.borrow().card_type()
+    }
+    fn target_data(&self) -> Vec<u8> {
+        self.borrow().target_data()
+    }
+    fn activate(&mut self) -> bool {
+        self.borrow_mut().activate()
+    }
+    fn exchange(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        self.borrow_mut().exchange(data)
+    }
+    fn communicate_thru(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        self.borrow_mut().communicate_thru(data)
+    }
+}
+
+/// Simulated PN532, see the [module documentation](self)
+pub struct SimulatedPn532 {
+    /// Number of times [`wait_ready`](Interface::wait_ready) returns `Poll::Pending`
+    /// before each ACK or response frame can be read
+    pub latency: u32,
+    /// Data of the GetFirmwareVersion response: IC, Ver, Rev and Support
+    pub firmware_version: [u8; 4],
+    targets: Vec<Box<dyn VirtualTarget>>,
+    /// Indices into `targets`, the logical target number is the position + 1
+    activated: Vec<usize>,
+    rf_field: bool,
+    max_retries_passive_activation: u8,
+    /// Frames that are sent once the current frame was read
+    pending: VecDeque<Vec<u8>>,
+    /// Unread bytes of the current frame
+    current: VecDeque<u8>,
+    polls: u32,
+    last_response: Option<Vec<u8>>,
+}
+
+/// Frame is invalid at the application level and is answered with a syntax error frame
+struct SyntaxError;
+
+impl SimulatedPn532 {
+    /// A simulated PN532 v1.6 without targets in its field
+    pub fn new() -> Self {
+        SimulatedPn532 {
+            latency: 0,
+            firmware_version: [0x32, 0x01, 0x06, 0x07],
+            targets: Vec::new(),
+            activated: Vec::new(),
+            rf_field: false,
+            max_retries_passive_activation: 0xFF,
+            pending: VecDeque::new(),
+            current: VecDeque::new(),
+            polls: 0,
+            last_response: None,
+        }
+    }
+
+    /// Places `target` in the field and returns its index
+    pub fn add_target(&mut self, target: impl VirtualTarget + 'static) -> usize {
+        self.targets.push(Box::new(target));
+        self.targets.len() - 1
+    }
+
+    /// Removes the target at `index` from the field, which also deactivates all targets
+    pub fn remove_target(&mut self, index: usize) -> Box<dyn VirtualTarget> {
+        self.activated.clear();
+        self.targets.remove(index)
+    }
+
+    /// Returns `true` if the RF field is switched on
+    pub fn rf_field(&self) -> bool {
+        self.rf_field
+    }
+
+    fn receive(&mut self, frame: &[u8]) {
+        self.current.clear();
+        let Some(start) = frame.windows(2).position(|window| window == [0x00, 0xFF]) else {
+            return;
+        };
+        let (len, body) = match &frame[start + 2..] {
+            // ACK frame: abort the current command
+            [0x00, 0xFF, ..] => return self.pending.clear(),
+            // NACK frame: send the last response frame again
+            [0xFF, 0x00, ..] => {
+                self.pending.extend(self.last_response.clone());
+                return;
+            }
+            [0xFF, 0xFF, len_msb, len_lsb, lcs, body @ ..] => {
+                if len_msb.wrapping_add(*len_lsb).wrapping_add(*lcs) != 0 {
+                    return;
+                }
+                (u16::from_be_bytes([*len_msb, *len_lsb]) as usize, body)
+            }
+            [len, lcs, body @ ..] => {
+                if len.wrapping_add(*lcs) != 0 {
+                    return;
+                }
+                (*len as usize, body)
+            }
+            _ => return,
+        };
+        let Some(body_and_dcs) = body.get(..len + 1) else {
+            return;
+        };
+        if body_and_dcs.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+            return;
+        }
+        self.pending.clear();
+        self.pending.push_back(ACK.to_vec());
+        let response = match &body[..len] {
+            [HOST_TO_PN532, command, params @ ..] => match self.execute(*command, params) {
+                Ok(Some(data)) => information_frame(*command + 1, &data),
+                Ok(None) => return,
+                Err(SyntaxError) => ERROR_FRAME.to_vec(),
+            },
+            _ => ERROR_FRAME.to_vec(),
+        };
+        self.last_response = Some(response.clone());
+        self.pending.push_back(response);
+    }
+
+    /// Returns the response data or `None` if the PN532 does not answer
+    fn execute(&mut self, command: u8, params: &[u8]) -> Result<Option<Vec<u8>>, SyntaxError> {
+        const GET_FIRMWARE_VERSION: u8 = Command::GetFirmwareVersion as u8;
+        const SAM_CONFIGURATION: u8 = Command::SAMConfiguration as u8;
+        const RF_CONFIGURATION: u8 = Command::RFConfiguration as u8;
+        const IN_LIST_PASSIVE_TARGET: u8 = Command::InListPassiveTarget as u8;
+        const IN_DATA_EXCHANGE: u8 = Command::InDataExchange as u8;
+        const IN_COMMUNICATE_THRU: u8 = Command::InCommunicateThru as u8;
+        match command {
+            GET_FIRMWARE_VERSION => Ok(Some(self.firmware_version.to_vec())),
+            SAM_CONFIGURATION => match params {
+                [1 | 3 | 4, ..] | [2, _, ..] => Ok(Some(Vec::new())),
+                _ => Err(SyntaxError),
+            },
+            RF_CONFIGURATION => self.rf_configuration(params).map(Some),
+            IN_LIST_PASSIVE_TARGET => self.in_list_passive_target(params),
+            IN_DATA_EXCHANGE => self.in_data_exchange(params).map(Some),
+            IN_COMMUNICATE_THRU => Ok(Some(self.in_communicate_thru(params))),
+            _ => Err(SyntaxError),
+        }
+    }
+
+    fn rf_configuration(&mut self, params: &[u8]) -> Result<Vec<u8>, SyntaxError> {
+        match params {
+            [0x01, field] => {
+                self.rf_field = field & 0x01 != 0;
+                if !self.rf_field {
+                    self.activated.clear();
+                }
+            }
+            [0x05, _, _, passive_activation] => {
+                self.max_retries_passive_activation = *passive_activation;
+            }
+            [0x02, _, _, _] | [0x04, _] | [0x0C, _, _, _] => {}
+            [0x0A, rest @ ..] if rest.len() == 11 => {}
+            [0x0B, rest @ ..] if rest.len() == 8 => {}
+            [0x0D, rest @ ..] if rest.len() == 9 => {}
+            _ => return Err(SyntaxError),
+        }
+        Ok(Vec::new())
+    }
+
+    fn in_list_passive_target(&mut self, params: &[u8]) -> Result<Option<Vec<u8>>, SyntaxError> {
+        let [max_tg @ (1 | 2), br_ty, initiator_data @ ..] = params else {
+            return Err(SyntaxError);
+        };
+        let card_type = match (br_ty, initiator_data.len()) {
+            (0x00, _) => CardType::IsoTypeA,
+            (0x01, 5) => CardType::FeliCa212kbps,
+            (0x02, 5) => CardType::FeliCa424kbps,
+            (0x03, 1..) => CardType::IsoTypeB,
+            (0x04, _) => CardType::Jewel,
+            _ => return Err(SyntaxError),
+        };
+        self.rf_field = true;
+        self.activated.clear();
+        let mut response = vec![0];
+        for (index, target) in self.targets.iter_mut().enumerate() {
+            if self.activated.len() == *max_tg as usize {
+                break;
+            }
+            if target.card_type() != card_type {
+                continue;
+            }
+            // the UID of an ISO/IEC 14443 Type A target is used for the cascade selection
+            let target_data = target.target_data();
+            if card_type == CardType::IsoTypeA
+                && !initiator_data.is_empty()
+                && target_data.get(4..4 + initiator_data.len()) != Some(initiator_data)
+            {
+                continue;
+            }
+            if target.activate() {
+                self.activated.push(index);
+                response.push(self.activated.len() as u8);
+                response.extend(target_data);
+            }
+        }
+        if self.activated.is_empty() && self.max_retries_passive_activation == 0xFF {
+            // retries forever until a target enters the field
+            return Ok(None);
+        }
+        response[0] = self.activated.len() as u8;
+        Ok(Some(response))
+    }
+
+    fn in_data_exchange(&mut self, params: &[u8]) -> Result<Vec<u8>, SyntaxError> {
+        let [tg, data @ ..] = params else {
+            return Err(SyntaxError);
+        };
+        let target = (*tg as usize)
+            .checked_sub(1)
+            .and_then(|tg| self.activated.get(tg))
+            .map(|&index| &mut self.targets[index]);
+        let Some(target) = target else {
+            return Ok(vec![ErrorCode::CommandNotAcceptable as u8]);
+        };
+        Ok(status_response(target.exchange(data)))
+    }
+
+    /// Exchanges `data` with the first activated target
+    fn in_communicate_thru(&mut self, data: &[u8]) -> Vec<u8> {
+        match self.activated.first() {
+            Some(&index) => status_response(self.targets[index].communicate_thru(data)),
+            None => vec![ErrorCode::CommandNotAcceptable as u8],
+        }
+    }
+}
+
+/// Prepends the status byte to the data of the target
+fn status_response(result: Result<Vec<u8>, ErrorCode>) -> Vec<u8> {
+    match result {
+        Ok(data) => [&[0x00], &data[..]].concat(),
+        Err(error_code) => vec![error_code as u8],
+    }
+}
+
+impl Default for SimulatedPn532 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interface for SimulatedPn532 {
+    type Error = Infallible;
+
+    fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        self.receive(frame);
+        Ok(())
+    }
+
+    fn wait_ready(&mut self) -> Poll<Result<(), Self::Error>> {
+        if !self.current.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+        if self.pending.is_empty() {
+            return Poll::Pending;
+        }
+        if self.polls < self.latency {
+            self.polls += 1;
+            return Poll::Pending;
+        }
+        self.polls = 0;
+        self.current.extend(self.pending.pop_front().unwrap());
+        Poll::Ready(Ok(()))
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        for byte in buf {
+            *byte = self.current.pop_front().unwrap_or(0);
+        }
+        Ok(())
+    }
+
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_frame_in_chunks(self, buf)
+    }
+
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        continue_frame_in_chunks(self, buf, len)
+    }
+}
+
+/// Encodes a response frame, using an extended information frame for more than 252 bytes of data
+fn information_frame(response_code: u8, data: &[u8]) -> Vec<u8> {
+    let len = data.len() + 2;
+    let mut frame = vec![0x00, 0x00, 0xFF];
+    if len > 0xFE {
+        let [len_msb, len_lsb] = (len as u16).to_be_bytes();
+        let lcs = 0u8.wrapping_sub(len_msb.wrapping_add(len_lsb));
+        frame.extend([0xFF, 0xFF, len_msb, len_lsb, lcs]);
+    } else {
+        frame.extend([len as u8, 0u8.wrapping_sub(len as u8)]);
+    }
+    frame.extend([PN532_TO_HOST, response_code]);
+    frame.extend_from_slice(data);
+    let sum = data
+        .iter()
+        .fold(PN532_TO_HOST.wrapping_add(response_code), |sum, &b| {
+            sum.wrapping_add(b)
+        });
+    frame.extend([0u8.wrapping_sub(sum), 0x00]);
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc_test_helper::NoOpTimer;
+    use crate::requests::{BorrowedRequest, SAMMode};
+    use crate::responses::{ListedTargets, Target};
+    use crate::{Error, Pn532, Request, TransactionState};
+    use core::time::Duration;
+
+    /// Type A target that echoes the exchanged data
+    struct EchoTarget;
+
+    impl VirtualTarget for EchoTarget {
+        fn card_type(&self) -> CardType {
+            CardType::IsoTypeA
+        }
+        fn target_data(&self) -> Vec<u8> {
+            vec![0x00, 0x44, 0x00, 0x04, 0x01, 0x02, 0x03, 0x04]
+        }
+        fn exchange(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+            match data {
+                [] => Err(ErrorCode::Timeout),
+                data => Ok(data.to_vec()),
+            }
+        }
+    }
+
+    fn pn532(simulator: SimulatedPn532) -> Pn532<SimulatedPn532, NoOpTimer, 64> {
+        Pn532::new(simulator, NoOpTimer)
+    }
+
+    #[test]
+    fn test_configuration() {
+        let mut pn532 = pn532(SimulatedPn532::new());
+        let request = Request::sam_configuration(SAMMode::Normal, false);
+        assert_eq!(pn532.process(&request, 0, Duration::ZERO), Ok(&[][..]));
+        let request = Request::new(Command::RFConfiguration, [0x01, 0x01]);
+        assert_eq!(pn532.process(&request, 0, Duration::ZERO), Ok(&[][..]));
+        assert!(pn532.interface.rf_field());
+        let request = Request::new(Command::RFConfiguration, [0x01]);
+        assert_eq!(
+            pn532.process(&request, 0, Duration::ZERO),
+            Err(Error::Syntax)
+        );
+    }
+
+    #[test]
+    fn test_list_and_exchange() {
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(EchoTarget);
+        let mut pn532 = pn532(simulator);
+
+        let data = [0x00, 0x12, 0x34];
+        let request = BorrowedRequest::new(Command::InDataExchange, &data[..]);
+        let response = pn532.process(request, 1, Duration::ZERO);
+        assert_eq!(response, Ok(&[ErrorCode::CommandNotAcceptable as u8][..]));
+
+        let response = pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 10, Duration::ZERO);
+        let expected = [0x01, 0x01, 0x00, 0x44, 0x00, 0x04, 0x01, 0x02, 0x03, 0x04];
+        assert_eq!(response, Ok(&expected[..]));
+
+        let data = [0x01, 0x12, 0x34];
+        let request = BorrowedRequest::new(Command::InDataExchange, &data[..]);
+        let response = pn532.process(request, 3, Duration::ZERO);
+        assert_eq!(response, Ok(&[0x00, 0x12, 0x34][..]));
+
+        let request = Request::new(Command::InDataExchange, [0x01]);
+        let response = pn532.process(&request, 1, Duration::ZERO);
+        assert_eq!(response, Ok(&[ErrorCode::Timeout as u8][..]));
+    }
+
+    #[test]
+    fn test_list_by_uid() {
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(EchoTarget);
+        let mut pn532 = pn532(simulator);
+        let request = Request::new(Command::RFConfiguration, [0x05, 0xFF, 0x01, 0x00]);
+        assert_eq!(pn532.process(&request, 0, Duration::ZERO), Ok(&[][..]));
+
+        let request = Request::inlist_iso_a_target_by_uid(&[0x01, 0x02, 0x03, 0x05]);
+        let response = pn532.process(&request, 10, Duration::ZERO);
+        assert_eq!(response, Ok(&[0x00][..]));
+        let request = Request::inlist_iso_a_target_by_uid(&[0x01, 0x02, 0x03, 0x04]);
+        let response = pn532.process_typed::<ListedTargets>(&request, 10, Duration::ZERO);
+        let listed = response.unwrap();
+        let mut targets = listed.targets(CardType::IsoTypeA);
+        let Some(Ok(Target::IsoA(target))) = targets.next() else {
+            panic!("expected a Type A target");
+        };
+        assert_eq!(target.uid, [0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(targets.next(), None);
+
+        let request = Request::try_inlist_iso_a_target_by_uid::<Infallible>(&[0x01; 11]);
+        assert_eq!(request, Err(Error::InvalidArgument));
+    }
+
+    #[test]
+    fn test_no_target_in_field() {
+        let mut pn532 = pn532(SimulatedPn532::new());
+        let mut transaction = pn532.start(&Request::INLIST_ONE_ISO_A_TARGET, 1).unwrap();
+        for _ in 0..10 {
+            assert!(transaction.poll().is_pending());
+        }
+        assert_eq!(transaction.state(), TransactionState::AckReceived);
+        transaction.abort().unwrap();
+
+        let request = Request::new(Command::RFConfiguration, [0x05, 0xFF, 0x01, 0x00]);
+        assert_eq!(pn532.process(&request, 0, Duration::ZERO), Ok(&[][..]));
+        let response = pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 1, Duration::ZERO);
+        assert_eq!(response, Ok(&[0x00][..]));
+    }
+
+    #[test]
+    fn test_latency_and_retransmission() {
+        let mut simulator = SimulatedPn532::new();
+        simulator.latency = 3;
+        let mut pn532 = pn532(simulator);
+        pn532.send(&Request::GET_FIRMWARE_VERSION).unwrap();
+        for _ in 0..3 {
+            assert!(pn532.interface.wait_ready().is_pending());
+        }
+        assert!(pn532.interface.wait_ready().is_ready());
+        pn532.receive_ack().unwrap();
+        while pn532.interface.wait_ready().is_pending() {}
+        let response = pn532.receive_response(Command::GetFirmwareVersion, 4);
+        assert_eq!(response, Ok(&[0x32, 0x01, 0x06, 0x07][..]));
+
+        pn532.request_retransmit().unwrap();
+        while pn532.interface.wait_ready().is_pending() {}
+        let response = pn532.receive_response(Command::GetFirmwareVersion, 4);
+        assert_eq!(response, Ok(&[0x32, 0x01, 0x06, 0x07][..]));
+    }
+
+    #[test]
+    fn test_invalid_frames() {
+        let mut pn532 = pn532(SimulatedPn532::new());
+        // wrong data checksum
+        let mut frame = [0x00, 0x00, 0xFF, 0x02, 0xFE, 0xD4, 0x02, 0x2B, 0x00];
+        pn532.interface.write(&mut frame).unwrap();
+        assert!(pn532.interface.wait_ready().is_pending());
+
+        let request = Request::new(Command::Diagnose, [0x00]);
+        assert_eq!(
+            pn532.process(&request, 0, Duration::ZERO),
+            Err(Error::Syntax)
+        );
+    }
+}
@@ -0,0 +1,976 @@
+//! In-memory models of common tags
+//!
+//! * [`Ntag21x`]: NTAG213, NTAG215 and NTAG216 with lock bits, password protection,
+//!   NFC counter and originality signature
+//! * [`MifareClassic`]: MIFARE Classic 1K and 4K with keys, access bits and value blocks
+//! * [`Type4Tag`]: ISO/IEC 14443-4 tag with an NFC Forum Type 4 NDEF application
+//!
+//! A NAK response of an NTAG is reported as [`ErrorCode::RfProtocolError`],
+//! a NAK response of a MIFARE Classic as [`ErrorCode::AuthenticationError`].
+//! After a NAK response, the tag is halted and answers nothing until it is activated again
+//! by InListPassiveTarget.
+//!
+//! ```
+//! # use pn532::doc_test_helper::NoOpTimer;
+//! use pn532::simulator::tags::{Ntag21x, NtagType};
+//! use pn532::simulator::SimulatedPn532;
+//! use pn532::{Pn532, Request};
+//! use pn532::IntoDuration; // trait for `ms()`, your HAL might have its own
+//!
+//! let mut simulator = SimulatedPn532::new();
+//! simulator.add_target(Ntag21x::new(NtagType::Ntag213, [0x04, 1, 2, 3, 4, 5, 6]));
+//! let mut pn532: Pn532<_, _, 32> = Pn532::new(simulator, NoOpTimer);
+//! pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 14, 50.ms()).unwrap();
+//! pn532.process(&Request::ntag_write(4, &[0xDE, 0xAD, 0xBE, 0xEF]), 1, 50.ms()).unwrap();
+//! let page = pn532.process(&Request::ntag_read(4), 17, 50.ms()).unwrap();
+//! assert_eq!(page[..5], [0x00, 0xDE, 0xAD, 0xBE, 0xEF]);
+//! ```
+use super::VirtualTarget;
+use crate::mifare::{
+    self, AccessBits, AccessCondition, CardSize, KeyType, Permission, SectorTrailer, ValueBlock,
+};
+use crate::ntag::{MemoryMap, Product};
+use crate::requests::{CardType, MifareCommand, NTAGCommand};
+use crate::ErrorCode;
+
+const NTAG_NAK: ErrorCode = ErrorCode::RfProtocolError;
+const MIFARE_NAK: ErrorCode = ErrorCode::AuthenticationError;
+
+/// Product of an [`Ntag21x`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum NtagType {
+    /// 45 pages, 144 bytes user memory
+    Ntag213,
+    /// 135 pages, 504 bytes user memory
+    Ntag215,
+    /// 231 pages, 888 bytes user memory
+    Ntag216,
+}
+
+impl NtagType {
+    const fn memory_map(self) -> MemoryMap {
+        match self {
+            NtagType::Ntag213 => Product::Ntag213.memory_map(),
+            NtagType::Ntag215 => Product::Ntag215.memory_map(),
+            NtagType::Ntag216 => Product::Ntag216.memory_map(),
+        }
+    }
+    const fn pages(self) -> usize {
+        self.memory_map().pages as usize
+    }
+    /// Page of the dynamic lock bytes, followed by CFG0, CFG1, PWD and PACK
+    const fn dynamic_lock_page(self) -> usize {
+        match self.memory_map().dynamic_lock_page {
+            Some(page) => page as usize,
+            None => unreachable!(),
+        }
+    }
+    /// Number of pages locked by each dynamic lock bit
+    const fn dynamic_lock_granularity(self) -> usize {
+        match self {
+            NtagType::Ntag213 => 2,
+            NtagType::Ntag215 | NtagType::Ntag216 => 16,
+        }
+    }
+    /// Storage size byte of the GET_VERSION response
+    const fn storage_size(self) -> u8 {
+        match self {
+            NtagType::Ntag213 => 0x0F,
+            NtagType::Ntag215 => 0x11,
+            NtagType::Ntag216 => 0x13,
+        }
+    }
+    /// Size of the data area in the capability container
+    const fn cc_size(self) -> u8 {
+        match self {
+            NtagType::Ntag213 => 0x12,
+            NtagType::Ntag215 => 0x3E,
+            NtagType::Ntag216 => 0x6D,
+        }
+    }
+}
+
+/// NTAG213, NTAG215 or NTAG216
+#[derive(Clone, Debug)]
+pub struct Ntag21x {
+    product: NtagType,
+    uid: [u8; 7],
+    pages: Vec<[u8; 4]>,
+    /// Originality signature returned by READ_SIG
+    pub signature: [u8; 32],
+    counter: u32,
+    counter_incremented: bool,
+    failed_auths: u32,
+    authenticated: bool,
+    halted: bool,
+}
+
+impl Ntag21x {
+    /// Factory state: empty NDEF message, no locks, no password protection
+    pub fn new(product: NtagType, uid: [u8; 7]) -> Self {
+        let mut pages = vec![[0; 4]; product.pages()];
+        pages[0] = [uid[0], uid[1], uid[2], 0x88 ^ uid[0] ^ uid[1] ^ uid[2]];
+        pages[1] = [uid[3], uid[4], uid[5], uid[6]];
+        pages[2] = [uid[3] ^ uid[4] ^ uid[5] ^ uid[6], 0x48, 0x00, 0x00];
+        pages[3] = [0xE1, 0x10, product.cc_size(), 0x00];
+        pages[4] = [0x03, 0x00, 0xFE, 0x00];
+        let cfg0 = product.dynamic_lock_page() + 1;
+        pages[cfg0] = [0x04, 0x00, 0x00, 0xFF];
+        pages[cfg0 + 1] = [0x00, 0x05, 0x00, 0x00];
+        pages[cfg0 + 2] = [0xFF; 4];
+        Ntag21x {
+            product,
+            uid,
+            pages,
+            signature: [0; 32],
+            counter: 0,
+            counter_incremented: false,
+            failed_auths: 0,
+            authenticated: false,
+            halted: false,
+        }
+    }
+
+    /// All pages including the configuration pages
+    pub fn pages(&self) -> &[[u8; 4]] {
+        &self.pages
+    }
+
+    /// Value of the NFC counter
+    pub fn counter(&self) -> u32 {
+        self.counter
+    }
+
+    /// Sets the password and the password acknowledge
+    pub fn set_password(&mut self, pwd: [u8; 4], pack: [u8; 2]) {
+        let pwd_page = self.product.dynamic_lock_page() + 3;
+        self.pages[pwd_page] = pwd;
+        self.pages[pwd_page + 1] = [pack[0], pack[1], 0x00, 0x00];
+    }
+
+    fn cfg0(&self) -> usize {
+        self.product.dynamic_lock_page() + 1
+    }
+
+    fn auth0(&self) -> usize {
+        self.pages[self.cfg0()][3] as usize
+    }
+
+    fn access(&self) -> u8 {
+        self.pages[self.cfg0() + 1][0]
+    }
+
+    fn read_protected(&self, page: usize) -> bool {
+        self.access() & 0x80 != 0 && page >= self.auth0() && !self.authenticated
+    }
+
+    fn write_protected(&self, page: usize) -> bool {
+        page >= self.auth0() && !self.authenticated
+    }
+
+    fn locked(&self, page: usize) -> bool {
+        let [_, _, lock0, lock1] = self.pages[2];
+        let dynamic_lock_page = self.product.dynamic_lock_page();
+        match page {
+            0..=2 => false,
+            3 => lock0 & 0x08 != 0,
+            4..=7 => lock0 & (1 << page) != 0,
+            8..=15 => lock1 & (1 << (page - 8)) != 0,
+            page if page < dynamic_lock_page => {
+                let bit = (page - 16) / self.product.dynamic_lock_granularity();
+                let [dyn0, dyn1, ..] = self.pages[dynamic_lock_page];
+                bit < 16 && u16::from_le_bytes([dyn0, dyn1]) & (1 << bit) != 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Page content as seen by READ and FAST_READ
+    fn read_page(&self, page: usize) -> [u8; 4] {
+        if page >= self.cfg0() + 2 {
+            // PWD and PACK always read as zero
+            [0; 4]
+        } else {
+            self.pages[page]
+        }
+    }
+
+    fn count_read(&mut self) {
+        if self.access() & 0x10 != 0 && !self.counter_incremented && self.counter < 0xFF_FFFF {
+            self.counter += 1;
+            self.counter_incremented = true;
+        }
+    }
+
+    fn read(&mut self, start: usize) -> Result<Vec<u8>, ErrorCode> {
+        if start >= self.pages.len() || self.read_protected(start) {
+            return Err(NTAG_NAK);
+        }
+        self.count_read();
+        let mut data = Vec::with_capacity(16);
+        for page in start..start + 4 {
+            // roll over to page 0 at the end of the memory or of the accessible area
+            let page = if page >= self.pages.len() {
+                page - self.pages.len()
+            } else if self.read_protected(page) {
+                page - self.auth0()
+            } else {
+                page
+            };
+            data.extend(self.read_page(page));
+        }
+        Ok(data)
+    }
+
+    fn fast_read(&mut self, start: usize, end: usize) -> Result<Vec<u8>, ErrorCode> {
+        if start > end || end >= self.pages.len() || self.read_protected(end) {
+            return Err(NTAG_NAK);
+        }
+        self.count_read();
+        Ok((start..=end)
+            .flat_map(|page| self.read_page(page))
+            .collect())
+    }
+
+    fn write(&mut self, page: usize, data: [u8; 4]) -> Result<Vec<u8>, ErrorCode> {
+        let cfg0 = self.cfg0();
+        if !(2..self.pages.len()).contains(&page) || self.write_protected(page) || self.locked(page)
+        {
+            return Err(NTAG_NAK);
+        }
+        if (cfg0..cfg0 + 2).contains(&page) && self.access() & 0x40 != 0 {
+            // CFGLCK: configuration is locked
+            return Err(NTAG_NAK);
+        }
+        let current = &mut self.pages[page];
+        if page == 2 {
+            // only the lock bytes can be written, bits can only be set
+            current[2] |= data[2];
+            current[3] |= data[3];
+        } else if page == 3 || page == self.product.dynamic_lock_page() {
+            // OTP bits and dynamic lock bits can only be set
+            for (byte, new) in current.iter_mut().zip(data) {
+                *byte |= new;
+            }
+        } else {
+            *current = data;
+        }
+        Ok(Vec::new())
+    }
+
+    fn pwd_auth(&mut self, pwd: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        let auth_limit = self.access() & 0x07;
+        if auth_limit != 0 && self.failed_auths >= 1 << auth_limit {
+            return Err(NTAG_NAK);
+        }
+        let pwd_page = self.cfg0() + 2;
+        if pwd != self.pages[pwd_page] {
+            self.failed_auths += 1;
+            return Err(NTAG_NAK);
+        }
+        self.failed_auths = 0;
+        self.authenticated = true;
+        Ok(self.pages[pwd_page + 1][..2].to_vec())
+    }
+
+    fn read_cnt(&mut self) -> Result<Vec<u8>, ErrorCode> {
+        let access = self.access();
+        if access & 0x10 == 0 || (access & 0x08 != 0 && !self.authenticated) {
+            return Err(NTAG_NAK);
+        }
+        Ok(self.counter.to_le_bytes()[..3].to_vec())
+    }
+
+    fn handle(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        const GET_VERSION: u8 = NTAGCommand::GetVersion as u8;
+        const READ: u8 = NTAGCommand::Read as u8;
+        const FAST_READ: u8 = NTAGCommand::FastRead as u8;
+        const WRITE: u8 = NTAGCommand::Write as u8;
+        const COMP_WRITE: u8 = NTAGCommand::CompWrite as u8;
+        const READ_CNT: u8 = NTAGCommand::ReadCnt as u8;
+        const PWD_AUTH: u8 = NTAGCommand::PwdAuth as u8;
+        const READ_SIG: u8 = NTAGCommand::ReadSig as u8;
+        match data {
+            [GET_VERSION] => Ok(vec![
+                0x00,
+                0x04,
+                0x04,
+                0x02,
+                0x01,
+                0x00,
+                self.product.storage_size(),
+                0x03,
+            ]),
+            [READ, page] => self.read(*page as usize),
+            [FAST_READ, start, end] => self.fast_read(*start as usize, *end as usize),
+            [WRITE, page, a, b, c, d] => self.write(*page as usize, [*a, *b, *c, *d]),
+            // the PN532 sends both parts of COMPATIBILITY_WRITE
+            [COMP_WRITE, page, data @ ..] if data.len() == 16 => {
+                self.write(*page as usize, data[..4].try_into().unwrap())
+            }
+            [READ_CNT, 0x02] => self.read_cnt(),
+            [PWD_AUTH, pwd @ ..] if pwd.len() == 4 => self.pwd_auth(pwd),
+            [READ_SIG, 0x00] => Ok(self.signature.to_vec()),
+            _ => Err(NTAG_NAK),
+        }
+    }
+}
+
+impl VirtualTarget for Ntag21x {
+    fn card_type(&self) -> CardType {
+        CardType::IsoTypeA
+    }
+    fn target_data(&self) -> Vec<u8> {
+        [&[0x00, 0x44, 0x00, 0x07][..], &self.uid].concat()
+    }
+    fn activate(&mut self) -> bool {
+        self.halted = false;
+        self.authenticated = false;
+        self.counter_incremented = false;
+        true
+    }
+    fn exchange(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        if self.halted {
+            return Err(ErrorCode::Timeout);
+        }
+        let result = self.handle(data);
+        if result.is_err() {
+            self.halted = true;
+        }
+        result
+    }
+}
+
+/// MIFARE Classic 1K or 4K
+#[derive(Clone, Debug)]
+pub struct MifareClassic {
+    product: CardSize,
+    uid: [u8; 4],
+    blocks: Vec<[u8; 16]>,
+    /// Authenticated sector and key
+    authenticated: Option<(usize, KeyType)>,
+    /// Value block loaded by Increment, Decrement or Restore
+    transfer_buffer: Option<ValueBlock>,
+    halted: bool,
+}
+
+impl MifareClassic {
+    /// Transport configuration: default keys `FF FF FF FF FF FF` and access bits `FF 07 80`
+    pub fn new(product: CardSize, uid: [u8; 4]) -> Self {
+        let mut blocks = vec![[0; 16]; product.blocks()];
+        let bcc = uid.iter().fold(0, |bcc, b| bcc ^ b);
+        let (sak, atqa) = Self::sak_atqa(product);
+        blocks[0][..4].copy_from_slice(&uid);
+        blocks[0][4] = bcc;
+        blocks[0][5] = sak;
+        blocks[0][6..8].copy_from_slice(&[atqa[1], atqa[0]]);
+        let mut tag = MifareClassic {
+            product,
+            uid,
+            blocks,
+            authenticated: None,
+            transfer_buffer: None,
+            halted: false,
+        };
+        for sector in 0..product.sectors() {
+            let trailer = mifare::trailer_block_of(sector);
+            tag.blocks[trailer as usize] = SectorTrailer::TRANSPORT.to_bytes();
+        }
+        tag
+    }
+
+    const fn sak_atqa(product: CardSize) -> (u8, [u8; 2]) {
+        match product {
+            CardSize::Classic1K => (0x08, [0x00, 0x04]),
+            CardSize::Classic4K => (0x18, [0x00, 0x02]),
+        }
+    }
+
+    /// All blocks including the sector trailers
+    pub fn blocks(&self) -> &[[u8; 16]] {
+        &self.blocks
+    }
+
+    /// Mutable access to all blocks, e.g. to prepare keys and access bits
+    pub fn blocks_mut(&mut self) -> &mut [[u8; 16]] {
+        &mut self.blocks
+    }
+
+    fn sector(block: usize) -> usize {
+        mifare::sector_of(block as u8) as usize
+    }
+
+    fn first_block(sector: usize) -> usize {
+        mifare::first_block_of(sector as u8) as usize
+    }
+
+    fn trailer_block(&self, sector: usize) -> usize {
+        mifare::trailer_block_of(sector as u8) as usize
+    }
+
+    /// Access condition of `block`, invalid access bits lock the sector like on a real card
+    fn access_condition(&self, block: usize) -> AccessCondition {
+        let sector = Self::sector(block);
+        let trailer = &self.blocks[self.trailer_block(sector)];
+        let Ok(access_bits) = AccessBits::try_from([trailer[6], trailer[7], trailer[8]]) else {
+            return AccessCondition::new(true, true, true);
+        };
+        // sectors with 16 blocks have groups of 5 blocks
+        let offset = block - Self::first_block(sector);
+        let group = if sector < 32 { offset } else { offset / 5 };
+        match group {
+            0..=2 => access_bits.data[group],
+            _ => access_bits.trailer,
+        }
+    }
+
+    /// Returns `true` if the authenticated key has the `permission` for `block`
+    fn permitted(&self, block: usize, permission: Permission) -> bool {
+        match self.authenticated {
+            Some((sector, key_type)) if sector == Self::sector(block) => {
+                let trailer = self.access_condition(self.trailer_block(sector));
+                // a readable key B cannot be used
+                let key_b_readable = trailer.trailer_permissions().key_b_read != Permission::Never;
+                permission.allows(key_type) && !(key_type == KeyType::B && key_b_readable)
+            }
+            _ => false,
+        }
+    }
+
+    fn is_trailer(&self, block: usize) -> bool {
+        block == self.trailer_block(Self::sector(block))
+    }
+
+    fn authenticate(
+        &mut self,
+        key_type: KeyType,
+        block: usize,
+        key: &[u8],
+    ) -> Result<(), ErrorCode> {
+        if block >= self.blocks.len() {
+            return Err(MIFARE_NAK);
+        }
+        let sector = Self::sector(block);
+        let trailer = &self.blocks[self.trailer_block(sector)];
+        let expected = match key_type {
+            KeyType::A => &trailer[..6],
+            KeyType::B => &trailer[10..],
+        };
+        if key != expected {
+            return Err(MIFARE_NAK);
+        }
+        self.authenticated = Some((sector, key_type));
+        Ok(())
+    }
+
+    fn read(&self, block: usize) -> Result<Vec<u8>, ErrorCode> {
+        let condition = self.access_condition(block);
+        if !self.is_trailer(block) {
+            if !self.permitted(block, condition.data_permissions().read) {
+                return Err(MIFARE_NAK);
+            }
+            return Ok(self.blocks[block].to_vec());
+        }
+        if self.authenticated.map(|(sector, _)| sector) != Some(Self::sector(block)) {
+            return Err(MIFARE_NAK);
+        }
+        // key A is never readable
+        let permissions = condition.trailer_permissions();
+        let mut data = [0; 16];
+        if self.permitted(block, permissions.access_bits_read) {
+            data[6..10].copy_from_slice(&self.blocks[block][6..10]);
+        }
+        if self.permitted(block, permissions.key_b_read) {
+            data[10..].copy_from_slice(&self.blocks[block][10..]);
+        }
+        Ok(data.to_vec())
+    }
+
+    fn write(&mut self, block: usize, data: &[u8]) -> Result<(), ErrorCode> {
+        let condition = self.access_condition(block);
+        if block == 0 {
+            // manufacturer block
+            return Err(MIFARE_NAK);
+        }
+        if !self.is_trailer(block) {
+            if !self.permitted(block, condition.data_permissions().write) {
+                return Err(MIFARE_NAK);
+            }
+            self.blocks[block].copy_from_slice(data);
+            return Ok(());
+        }
+        let permissions = condition.trailer_permissions();
+        let (key_a_write, access_write, key_b_write) = (
+            self.permitted(block, permissions.key_a_write),
+            self.permitted(block, permissions.access_bits_write),
+            self.permitted(block, permissions.key_b_write),
+        );
+        if !(key_a_write || access_write || key_b_write) {
+            return Err(MIFARE_NAK);
+        }
+        let trailer = &mut self.blocks[block];
+        if key_a_write {
+            trailer[..6].copy_from_slice(&data[..6]);
+        }
+        if access_write {
+            trailer[6..10].copy_from_slice(&data[6..10]);
+        }
+        if key_b_write {
+            trailer[10..].copy_from_slice(&data[10..]);
+        }
+        Ok(())
+    }
+
+    fn value_operation(
+        &mut self,
+        command: u8,
+        block: usize,
+        operand: i32,
+    ) -> Result<(), ErrorCode> {
+        const INCREMENT: u8 = MifareCommand::Increment as u8;
+        const DECREMENT: u8 = MifareCommand::Decrement as u8;
+        if self.is_trailer(block) {
+            return Err(MIFARE_NAK);
+        }
+        let permissions = self.access_condition(block).data_permissions();
+        let allowed = if command == INCREMENT {
+            permissions.increment
+        } else {
+            permissions.decrement
+        };
+        if !self.permitted(block, allowed) {
+            return Err(MIFARE_NAK);
+        }
+        let mut value_block = ValueBlock::try_from(self.blocks[block]).map_err(|()| MIFARE_NAK)?;
+        let value = value_block.value;
+        let result = match command {
+            INCREMENT => value.checked_add(operand),
+            DECREMENT => value.checked_sub(operand),
+            _ => Some(value),
+        };
+        value_block.value = result.ok_or(MIFARE_NAK)?;
+        self.transfer_buffer = Some(value_block);
+        Ok(())
+    }
+
+    fn transfer(&mut self, block: usize) -> Result<(), ErrorCode> {
+        let decrement = self.access_condition(block).data_permissions().decrement;
+        if self.is_trailer(block) || !self.permitted(block, decrement) {
+            return Err(MIFARE_NAK);
+        }
+        let value_block = self.transfer_buffer.take().ok_or(MIFARE_NAK)?;
+        self.blocks[block] = value_block.to_bytes();
+        Ok(())
+    }
+
+    fn handle(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        const AUTH_A: u8 = MifareCommand::AuthenticationWithKeyA as u8;
+        const AUTH_B: u8 = MifareCommand::AuthenticationWithKeyB as u8;
+        const READ: u8 = MifareCommand::Read as u8;
+        const WRITE: u8 = MifareCommand::Write as u8;
+        const INCREMENT: u8 = MifareCommand::Increment as u8;
+        const DECREMENT: u8 = MifareCommand::Decrement as u8;
+        const RESTORE: u8 = MifareCommand::Restore as u8;
+        const TRANSFER: u8 = MifareCommand::Transfer as u8;
+        let (command, block, params) = match data {
+            [command, block, params @ ..] if (*block as usize) < self.blocks.len() => {
+                (*command, *block as usize, params)
+            }
+            _ => return Err(MIFARE_NAK),
+        };
+        match (command, params) {
+            (AUTH_A | AUTH_B, [key @ .., u0, u1, u2, u3]) if key.len() == 6 => {
+                if [*u0, *u1, *u2, *u3] != self.uid {
+                    return Err(MIFARE_NAK);
+                }
+                let key_type = if command == AUTH_A {
+                    KeyType::A
+                } else {
+                    KeyType::B
+                };
+                self.authenticate(key_type, block, key)?;
+                Ok(Vec::new())
+            }
+            (READ, []) => self.read(block),
+            (WRITE, data) if data.len() == 16 => self.write(block, data).map(|()| Vec::new()),
+            (INCREMENT | DECREMENT | RESTORE, [a, b, c, d]) => {
+                let operand = i32::from_le_bytes([*a, *b, *c, *d]);
+                self.value_operation(command, block, operand)?;
+                Ok(Vec::new())
+            }
+            (TRANSFER, []) => self.transfer(block).map(|()| Vec::new()),
+            _ => Err(MIFARE_NAK),
+        }
+    }
+}
+
+impl VirtualTarget for MifareClassic {
+    fn card_type(&self) -> CardType {
+        CardType::IsoTypeA
+    }
+    fn target_data(&self) -> Vec<u8> {
+        let (sak, atqa) = Self::sak_atqa(self.product);
+        [&atqa[..], &[sak, 0x04], &self.uid].concat()
+    }
+    fn activate(&mut self) -> bool {
+        self.halted = false;
+        self.authenticated = None;
+        self.transfer_buffer = None;
+        true
+    }
+    fn exchange(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        if self.halted {
+            return Err(ErrorCode::Timeout);
+        }
+        let result = self.handle(data);
+        if result.is_err() {
+            self.halted = true;
+            self.authenticated = None;
+        }
+        result
+    }
+}
+
+/// NDEF application of a Type 4 tag
+const NDEF_APPLICATION: [u8; 7] = [0xD2, 0x76, 0x00, 0x00, 0x85, 0x01, 0x01];
+const CC_FILE: [u8; 2] = [0xE1, 0x03];
+const NDEF_FILE: [u8; 2] = [0xE1, 0x04];
+const SW_OK: [u8; 2] = [0x90, 0x00];
+const SW_NOT_FOUND: [u8; 2] = [0x6A, 0x82];
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum SelectedFile {
+    None,
+    Application,
+    Cc,
+    Ndef,
+}
+
+/// ISO/IEC 14443-4 tag with an NFC Forum Type 4 NDEF application
+///
+/// Supports SELECT, READ BINARY and UPDATE BINARY on the capability container and the NDEF file.
+#[derive(Clone, Debug)]
+pub struct Type4Tag {
+    uid: [u8; 7],
+    /// NLEN followed by the NDEF message
+    ndef_file: Vec<u8>,
+    selected: SelectedFile,
+}
+
+impl Type4Tag {
+    /// Tag with an empty NDEF file of `ndef_file_size` bytes including the two length bytes
+    pub fn new(uid: [u8; 7], ndef_file_size: u16) -> Self {
+        Type4Tag {
+            uid,
+            ndef_file: vec![0; ndef_file_size.max(2) as usize],
+            selected: SelectedFile::None,
+        }
+    }
+
+    /// The NDEF message stored in the NDEF file
+    pub fn ndef_message(&self) -> &[u8] {
+        let len = u16::from_be_bytes([self.ndef_file[0], self.ndef_file[1]]) as usize;
+        &self.ndef_file[2..(2 + len).min(self.ndef_file.len())]
+    }
+
+    /// Stores `message` in the NDEF file, it is truncated to the file size
+    pub fn set_ndef_message(&mut self, message: &[u8]) {
+        let len = message.len().min(self.ndef_file.len() - 2);
+        self.ndef_file[..2].copy_from_slice(&(len as u16).to_be_bytes());
+        self.ndef_file[2..2 + len].copy_from_slice(&message[..len]);
+    }
+
+    fn capability_container(&self) -> Vec<u8> {
+        let [size_msb, size_lsb] = (self.ndef_file.len() as u16).to_be_bytes();
+        vec![
+            0x00,
+            0x0F, // CCLEN
+            0x20, // mapping version 2.0
+            0x00,
+            0xFF, // MLe
+            0x00,
+            0xFF, // MLc
+            0x04,
+            0x06, // NDEF file control TLV
+            NDEF_FILE[0],
+            NDEF_FILE[1],
+            size_msb,
+            size_lsb,
+            0x00, // read access
+            0x00, // write access
+        ]
+    }
+
+    fn handle(&mut self, apdu: &[u8]) -> Vec<u8> {
+        let [cla, ins, p1, p2, body @ ..] = apdu else {
+            return vec![0x67, 0x00];
+        };
+        if *cla != 0x00 {
+            return vec![0x6E, 0x00];
+        }
+        let offset = u16::from_be_bytes([*p1, *p2]) as usize;
+        match (ins, p1, p2, body) {
+            // SELECT by name
+            (0xA4, 0x04, 0x00, [0x07, name @ .., 0x00]) | (0xA4, 0x04, 0x00, [0x07, name @ ..])
+                if name == NDEF_APPLICATION =>
+            {
+                self.selected = SelectedFile::Application;
+                SW_OK.to_vec()
+            }
+            // SELECT by file identifier
+            (0xA4, 0x00, 0x0C, [0x02, file_id_msb, file_id_lsb])
+                if self.selected != SelectedFile::None =>
+            {
+                match [*file_id_msb, *file_id_lsb] {
+                    CC_FILE => self.selected = SelectedFile::Cc,
+                    NDEF_FILE => self.selected = SelectedFile::Ndef,
+                    _ => return SW_NOT_FOUND.to_vec(),
+                }
+                SW_OK.to_vec()
+            }
+            (0xA4, ..) => SW_NOT_FOUND.to_vec(),
+            // READ BINARY
+            (0xB0, _, _, [le]) => {
+                let file = match self.selected {
+                    SelectedFile::Cc => self.capability_container(),
+                    SelectedFile::Ndef => self.ndef_file.clone(),
+                    _ => return vec![0x69, 0x86],
+                };
+                if offset > file.len() {
+                    return vec![0x6B, 0x00];
+                }
+                let end = (offset + *le as usize).min(file.len());
+                [&file[offset..end], &SW_OK].concat()
+            }
+            // UPDATE BINARY
+            (0xD6, _, _, [lc, data @ ..]) if *lc as usize == data.len() => {
+                if self.selected != SelectedFile::Ndef {
+                    return vec![0x69, 0x82];
+                }
+                if offset + data.len() > self.ndef_file.len() {
+                    return vec![0x6B, 0x00];
+                }
+                self.ndef_file[offset..offset + data.len()].copy_from_slice(data);
+                SW_OK.to_vec()
+            }
+            (0xB0 | 0xD6, ..) => vec![0x67, 0x00],
+            _ => vec![0x6D, 0x00],
+        }
+    }
+}
+
+impl VirtualTarget for Type4Tag {
+    fn card_type(&self) -> CardType {
+        CardType::IsoTypeA
+    }
+    fn target_data(&self) -> Vec<u8> {
+        let ats = [0x05, 0x75, 0x77, 0x81, 0x02];
+        [&[0x00, 0x44, 0x20, 0x07][..], &self.uid, &ats].concat()
+    }
+    fn activate(&mut self) -> bool {
+        self.selected = SelectedFile::None;
+        true
+    }
+    fn exchange(&mut self, data: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+        Ok(self.handle(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc_test_helper::NoOpTimer;
+    use crate::requests::{BorrowedRequest, Command};
+    use crate::simulator::SimulatedPn532;
+    use crate::{Pn532, Request};
+    use core::cell::RefCell;
+    use core::time::Duration;
+    use std::rc::Rc;
+
+    const UID: [u8; 7] = [0x04, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+
+    fn pn532(target: impl VirtualTarget + 'static) -> Pn532<SimulatedPn532, NoOpTimer, 64> {
+        let mut simulator = SimulatedPn532::new();
+        simulator.add_target(target);
+        let mut pn532 = Pn532::new(simulator, NoOpTimer);
+        list(&mut pn532);
+        pn532
+    }
+
+    fn list(pn532: &mut Pn532<SimulatedPn532, NoOpTimer, 64>) {
+        let response = pn532.process(&Request::INLIST_ONE_ISO_A_TARGET, 20, Duration::ZERO);
+        assert_eq!(response.unwrap()[0], 1);
+    }
+
+    fn exchange<'a>(pn532: &'a mut Pn532<SimulatedPn532, NoOpTimer, 64>, data: &[u8]) -> &'a [u8] {
+        let data = [&[0x01], data].concat();
+        let request = BorrowedRequest::new(Command::InDataExchange, &data);
+        pn532.process(request, 40, Duration::ZERO).unwrap()
+    }
+
+    #[test]
+    fn test_ntag_read_write() {
+        let mut pn532 = pn532(Ntag21x::new(NtagType::Ntag213, UID));
+        let page = pn532.process(&Request::ntag_read(3), 17, Duration::ZERO);
+        assert_eq!(page.unwrap()[..5], [0x00, 0xE1, 0x10, 0x12, 0x00]);
+
+        let write = Request::ntag_write(5, &[1, 2, 3, 4]);
+        assert_eq!(pn532.process(&write, 1, Duration::ZERO), Ok(&[0x00][..]));
+        let page = pn532.process(&Request::ntag_read(5), 17, Duration::ZERO);
+        assert_eq!(page.unwrap()[1..5], [1, 2, 3, 4]);
+
+        // static lock bit of page 5
+        let lock = Request::ntag_write(2, &[0, 0, 0x20, 0]);
+        assert_eq!(pn532.process(&lock, 1, Duration::ZERO), Ok(&[0x00][..]));
+        let write = Request::ntag_write(5, &[5, 6, 7, 8]);
+        assert_eq!(
+            pn532.process(&write, 1, Duration::ZERO),
+            Ok(&[NTAG_NAK as u8][..])
+        );
+        // halted after NAK
+        let page = pn532.process(&Request::ntag_read(5), 17, Duration::ZERO);
+        assert_eq!(page, Ok(&[ErrorCode::Timeout as u8][..]));
+        list(&mut pn532);
+        let page = pn532.process(&Request::ntag_read(5), 17, Duration::ZERO);
+        assert_eq!(page.unwrap()[1..5], [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_ntag_password() {
+        let ntag = Rc::new(RefCell::new(Ntag21x::new(NtagType::Ntag215, UID)));
+        {
+            let mut ntag = ntag.borrow_mut();
+            ntag.set_password([1, 2, 3, 4], [0xAB, 0xCD]);
+            // AUTH0 = 16, PROT = 1, AUTHLIM = 1
+            ntag.pages[131][3] = 16;
+            ntag.pages[132][0] = 0x81;
+        }
+        let mut pn532 = pn532(ntag.clone());
+
+        let page = pn532.process(&Request::ntag_read(16), 17, Duration::ZERO);
+        assert_eq!(page, Ok(&[NTAG_NAK as u8][..]));
+        list(&mut pn532);
+        let auth = Request::ntag_pwd_auth(&[1, 2, 3, 4]);
+        assert_eq!(
+            pn532.process(&auth, 3, Duration::ZERO),
+            Ok(&[0x00, 0xAB, 0xCD][..])
+        );
+        let write = Request::ntag_write(16, &[9, 9, 9, 9]);
+        assert_eq!(pn532.process(&write, 1, Duration::ZERO), Ok(&[0x00][..]));
+        assert_eq!(ntag.borrow().pages()[16], [9, 9, 9, 9]);
+        // PWD reads as zero
+        let page = pn532.process(&Request::ntag_read(133), 17, Duration::ZERO);
+        assert_eq!(page.unwrap()[1..5], [0; 4]);
+
+        // AUTHLIM of 2 failed attempts
+        for _ in 0..2 {
+            list(&mut pn532);
+            let auth = Request::ntag_pwd_auth(&[0, 0, 0, 0]);
+            let response = pn532.process(&auth, 3, Duration::ZERO);
+            assert_eq!(response, Ok(&[NTAG_NAK as u8][..]));
+        }
+        list(&mut pn532);
+        let auth = Request::ntag_pwd_auth(&[1, 2, 3, 4]);
+        let response = pn532.process(&auth, 3, Duration::ZERO);
+        assert_eq!(response, Ok(&[NTAG_NAK as u8][..]));
+    }
+
+    #[test]
+    fn test_ntag_counter_signature_and_version() {
+        let mut ntag = Ntag21x::new(NtagType::Ntag216, UID);
+        ntag.signature = [0x5A; 32];
+        ntag.pages[228][0] = 0x10; // NFC_CNT_EN
+        let mut pn532 = pn532(ntag);
+
+        assert_eq!(
+            exchange(&mut pn532, &[0x39, 0x02]),
+            [0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(exchange(&mut pn532, &[0x3A, 4, 5]).len(), 9);
+        exchange(&mut pn532, &[0x30, 4]);
+        assert_eq!(
+            exchange(&mut pn532, &[0x39, 0x02]),
+            [0x00, 0x01, 0x00, 0x00]
+        );
+        assert_eq!(exchange(&mut pn532, &[0x3C, 0x00])[1..], [0x5A; 32]);
+        let version = [0x00, 0x00, 0x04, 0x04, 0x02, 0x01, 0x00, 0x13, 0x03];
+        assert_eq!(exchange(&mut pn532, &[0x60]), version);
+    }
+
+    #[test]
+    fn test_mifare_classic() {
+        let uid = [0x12, 0x34, 0x56, 0x78];
+        let mut pn532 = pn532(MifareClassic::new(CardSize::Classic1K, uid));
+        let key = [0xFF; 6];
+        let auth = [&[0x60, 4][..], &key, &uid].concat();
+
+        assert_eq!(exchange(&mut pn532, &[0x30, 4]), [MIFARE_NAK as u8]);
+        list(&mut pn532);
+        assert_eq!(exchange(&mut pn532, &auth), [0x00]);
+        let data = [0xAA; 16];
+        assert_eq!(
+            exchange(&mut pn532, &[&[0xA0, 5][..], &data].concat()),
+            [0x00]
+        );
+        assert_eq!(exchange(&mut pn532, &[0x30, 5])[1..], data);
+        // key A is never readable
+        let trailer = exchange(&mut pn532, &[0x30, 7]);
+        assert_eq!(trailer[1..7], [0; 6]);
+        assert_eq!(trailer[7..11], [0xFF, 0x07, 0x80, 0x69]);
+
+        // wrong key halts the tag
+        let wrong_auth = [&[0x61, 4][..], &[0; 6], &uid].concat();
+        assert_eq!(exchange(&mut pn532, &wrong_auth), [MIFARE_NAK as u8]);
+        assert_eq!(exchange(&mut pn532, &auth), [ErrorCode::Timeout as u8]);
+        list(&mut pn532);
+        assert_eq!(exchange(&mut pn532, &auth), [0x00]);
+    }
+
+    #[test]
+    fn test_mifare_classic_value_block() {
+        let uid = [0x12, 0x34, 0x56, 0x78];
+        let mut pn532 = pn532(MifareClassic::new(CardSize::Classic4K, uid));
+        let auth = [&[0x60, 200][..], &[0xFF; 6], &uid].concat();
+        assert_eq!(exchange(&mut pn532, &auth), [0x00]);
+        let value_block = [
+            100, 0, 0, 0, !100, 0xFF, 0xFF, 0xFF, 100, 0, 0, 0, 200, !200, 200, !200,
+        ];
+        let write = [&[0xA0, 200][..], &value_block].concat();
+        assert_eq!(exchange(&mut pn532, &write), [0x00]);
+        assert_eq!(exchange(&mut pn532, &[0xC0, 200, 30, 0, 0, 0]), [0x00]);
+        assert_eq!(exchange(&mut pn532, &[0xB0, 200]), [0x00]);
+        let block = exchange(&mut pn532, &[0x30, 200]);
+        assert_eq!(block[1..5], 70i32.to_le_bytes());
+        assert_eq!(block[13..], [200, !200, 200, !200]);
+        // not a value block
+        assert_eq!(
+            exchange(&mut pn532, &[0xC1, 201, 1, 0, 0, 0]),
+            [MIFARE_NAK as u8]
+        );
+    }
+
+    #[test]
+    fn test_type4_tag() {
+        let mut tag = Type4Tag::new(UID, 64);
+        tag.set_ndef_message(&[0xD1, 0x01, 0x01, 0x54]);
+        let mut pn532 = pn532(tag);
+
+        let select_app = [
+            0x00, 0xA4, 0x04, 0x00, 0x07, 0xD2, 0x76, 0x00, 0x00, 0x85, 0x01, 0x01, 0x00,
+        ];
+        assert_eq!(exchange(&mut pn532, &select_app), [0x00, 0x90, 0x00]);
+        let select_cc = [0x00, 0xA4, 0x00, 0x0C, 0x02, 0xE1, 0x03];
+        assert_eq!(exchange(&mut pn532, &select_cc), [0x00, 0x90, 0x00]);
+        let cc = exchange(&mut pn532, &[0x00, 0xB0, 0x00, 0x00, 0x0F]);
+        assert_eq!(cc[10..14], [0xE1, 0x04, 0x00, 0x40]);
+
+        let select_ndef = [0x00, 0xA4, 0x00, 0x0C, 0x02, 0xE1, 0x04];
+        assert_eq!(exchange(&mut pn532, &select_ndef), [0x00, 0x90, 0x00]);
+        let ndef = exchange(&mut pn532, &[0x00, 0xB0, 0x00, 0x00, 0x06]);
+        assert_eq!(ndef, [0x00, 0x00, 0x04, 0xD1, 0x01, 0x01, 0x54, 0x90, 0x00]);
+        let update = [0x00, 0xD6, 0x00, 0x00, 0x02, 0x00, 0x00];
+        assert_eq!(exchange(&mut pn532, &update), [0x00, 0x90, 0x00]);
+        let ndef = exchange(&mut pn532, &[0x00, 0xB0, 0x00, 0x00, 0x02]);
+        assert_eq!(ndef, [0x00, 0x00, 0x00, 0x90, 0x00]);
+    }
+}
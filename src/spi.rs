@@ -5,6 +5,8 @@
 //!
 //! The SPI peripheral should be in **lsb mode**.
 //! If your peripheral cannot be set to **lsb mode** you need to enable the `msb-spi` feature of this crate.
+//!
+//! A frame can be read in several read transactions, as long as each one starts with [`PN532_SPI_DATAREAD`].
 use core::convert::Infallible;
 use core::fmt::Debug;
 use core::task::Poll;
@@ -12,6 +14,7 @@ use core::task::Poll;
 use embedded_hal::digital::InputPin;
 use embedded_hal::spi::{Operation, SpiDevice};
 
+use crate::protocol::{continue_frame_in_chunks, read_frame_in_chunks};
 use crate::Interface;
 
 #[cfg(feature = "msb-spi")]
@@ -82,6 +85,15 @@ where
         }
         Ok(())
     }
+
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        // every read transaction continues the frame where the last one stopped
+        read_frame_in_chunks(self, buf)
+    }
+
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        continue_frame_in_chunks(self, buf, len)
+    }
 }
 
 /// SPI Interface with IRQ pin
@@ -134,6 +146,78 @@ where
         }
         Ok(())
     }
+
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        // every read transaction continues the frame where the last one stopped
+        read_frame_in_chunks(self, buf)
+    }
+
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        continue_frame_in_chunks(self, buf, len)
+    }
+}
+
+/// Async SPI Interface with IRQ pin
+///
+/// Readiness is awaited until the IRQ pin is low instead of being polled.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Clone, Debug)]
+pub struct AsyncSPIInterfaceWithIrq<SPI, IRQ>
+where
+    SPI: embedded_hal_async::spi::SpiDevice,
+    IRQ: embedded_hal_async::digital::Wait<Error = Infallible>,
+{
+    pub spi: SPI,
+    pub irq: IRQ,
+}
+
+#[cfg(feature = "async")]
+impl<SPI, IRQ> crate::AsyncInterface for AsyncSPIInterfaceWithIrq<SPI, IRQ>
+where
+    SPI: embedded_hal_async::spi::SpiDevice,
+    IRQ: embedded_hal_async::digital::Wait<Error = Infallible>,
+{
+    type Error = <SPI as embedded_hal::spi::ErrorType>::Error;
+
+    async fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        #[cfg(feature = "msb-spi")]
+        for byte in frame.iter_mut() {
+            *byte = byte.reverse_bits();
+        }
+        self.spi
+            .transaction(&mut [
+                Operation::Write(&[PN532_SPI_DATAWRITE]),
+                Operation::Write(frame),
+            ])
+            .await
+    }
+
+    async fn wait_ready(&mut self) -> Result<(), Self::Error> {
+        // resolves immediately if the IRQ pin is already low
+        // infallible unwrap because of IRQ bound
+        self.irq.wait_for_low().await.unwrap();
+        Ok(())
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.spi
+            .transaction(&mut [
+                Operation::Write(&[PN532_SPI_DATAREAD]),
+                Operation::Read(buf),
+            ])
+            .await?;
+
+        #[cfg(feature = "msb-spi")]
+        for byte in buf.iter_mut() {
+            *byte = byte.reverse_bits();
+        }
+        Ok(())
+    }
+
+    async fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        crate::protocol::continue_frame_in_chunks_async(self, buf, len).await
+    }
 }
 
 #[cfg(test)]
@@ -187,6 +271,32 @@ mod tests {
         spi.spi.done();
     }
 
+    #[test]
+    fn test_spi_read_frame() {
+        let header = [0x00, 0x00, 0xFF, 0x02, 0xFE];
+        let rest = [0xD5, 0x15, 0x16, 0x00];
+        let mut spi = SPIInterface {
+            spi: SpiMock::new(&[
+                SpiTransaction::transaction_start(),
+                SpiTransaction::write(as_lsb(0x03)),
+                SpiTransaction::read_vec(header.iter().map(|&b| as_lsb(b)).collect()),
+                SpiTransaction::transaction_end(),
+                SpiTransaction::transaction_start(),
+                SpiTransaction::write(as_lsb(0x03)),
+                SpiTransaction::read_vec(rest.iter().map(|&b| as_lsb(b)).collect()),
+                SpiTransaction::transaction_end(),
+            ]),
+        };
+
+        let mut buf = [0; 32];
+        let len = spi.read_frame(&mut buf).unwrap();
+        assert_eq!(len, 9);
+        assert_eq!(buf[..5], header);
+        assert_eq!(buf[5..9], rest);
+
+        spi.spi.done();
+    }
+
     #[test]
     fn test_spi_with_irq() {
         let mut spi = SPIInterfaceWithIrq {
@@ -220,4 +330,37 @@ mod tests {
         spi.spi.done();
         spi.irq.mock.done();
     }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_spi_with_irq() {
+        use crate::test_helper::block_on;
+        use crate::AsyncInterface;
+
+        let mut spi = AsyncSPIInterfaceWithIrq {
+            spi: SpiMock::new(&[
+                // write
+                SpiTransaction::transaction_start(),
+                SpiTransaction::write(as_lsb(0x01)),
+                SpiTransaction::write_vec(vec![as_lsb(1), as_lsb(2)]),
+                SpiTransaction::transaction_end(),
+                // read
+                SpiTransaction::transaction_start(),
+                SpiTransaction::write(as_lsb(0x03)),
+                SpiTransaction::read_vec(vec![as_lsb(3), as_lsb(4)]),
+                SpiTransaction::transaction_end(),
+            ]),
+            irq: PinMock::new(&[DigitalTransaction::wait_for_state(State::Low)]),
+        };
+
+        block_on(spi.write(&mut [1, 2])).unwrap();
+        block_on(spi.wait_ready()).unwrap();
+
+        let mut buf = [0, 0];
+        block_on(spi.read(&mut buf)).unwrap();
+        assert_eq!(buf, [3, 4]);
+
+        spi.spi.done();
+        spi.irq.mock.done();
+    }
 }
@@ -0,0 +1,219 @@
+//! Tracing of the frames exchanged with the PN532
+//!
+//! Wrap any [`Interface`] in a [`TracingInterface`] to pass every written and read frame
+//! to an [`Observer`].
+//!
+//! ```
+//! # use pn532::doc_test_helper::{NoOpSPI, NoOpTimer};
+//! use pn532::spi::SPIInterface;
+//! use pn532::trace::{Direction, FrameKind, TracingInterface};
+//! use pn532::Pn532;
+//!
+//! # let spi = NoOpSPI;
+//! # let timer = NoOpTimer;
+//! let interface = TracingInterface {
+//!     interface: SPIInterface { spi },
+//!     observer: |timestamp: u32, direction: Direction, kind: FrameKind, frame: &[u8]| {
+//!         println!("{timestamp} {direction:?} {kind:?}: {frame:02X?}");
+//!     },
+//!     clock: || 0u32, // e.g. milliseconds since startup
+//! };
+//! let mut pn532: Pn532<_, _, 32> = Pn532::new(interface, timer);
+//! ```
+use crate::Interface;
+use core::task::Poll;
+
+/// Direction of a traced frame
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// Written by the host controller
+    HostToPn532,
+    /// Read by the host controller
+    Pn532ToHost,
+}
+
+/// Kind of a traced frame
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameKind {
+    /// Information frame sent by the host controller
+    Command,
+    /// ACK frame
+    Ack,
+    /// NACK frame
+    Nack,
+    /// Information or error frame sent by the PN532
+    Response,
+    /// No start code was found
+    Unknown,
+}
+
+impl FrameKind {
+    /// Classifies the frame in `frame` by the bytes following its start code
+    pub fn of(direction: Direction, frame: &[u8]) -> FrameKind {
+        let Some(start) = frame.windows(2).position(|window| window == [0x00, 0xFF]) else {
+            return FrameKind::Unknown;
+        };
+        match (frame.get(start + 2..start + 4), direction) {
+            (Some([0x00, 0xFF]), _) => FrameKind::Ack,
+            (Some([0xFF, 0x00]), _) => FrameKind::Nack,
+            (_, Direction::HostToPn532) => FrameKind::Command,
+            (_, Direction::Pn532ToHost) => FrameKind::Response,
+        }
+    }
+}
+
+/// Receives every frame that is written to or read from the PN532
+///
+/// Implemented for closures taking the same arguments as [`observe`](Observer::observe).
+pub trait Observer<Ts> {
+    /// Called with the time from the clock of the [`TracingInterface`] and the frame
+    fn observe(&mut self, timestamp: Ts, direction: Direction, kind: FrameKind, frame: &[u8]);
+}
+
+impl<Ts, F: FnMut(Ts, Direction, FrameKind, &[u8])> Observer<Ts> for F {
+    fn observe(&mut self, timestamp: Ts, direction: Direction, kind: FrameKind, frame: &[u8]) {
+        self(timestamp, direction, kind, frame)
+    }
+}
+
+/// [`Interface`] that passes every frame of `interface` to `observer`
+///
+/// `clock` is called for the timestamp of every frame.
+/// Use `|| ()` if no timestamps are needed.
+#[derive(Clone, Debug)]
+pub struct TracingInterface<I, O, C> {
+    pub interface: I,
+    pub observer: O,
+    pub clock: C,
+}
+
+impl<I, O, C, Ts> TracingInterface<I, O, C>
+where
+    O: Observer<Ts>,
+    C: FnMut() -> Ts,
+{
+    fn trace(&mut self, direction: Direction, frame: &[u8]) {
+        let timestamp = (self.clock)();
+        let kind = FrameKind::of(direction, frame);
+        self.observer.observe(timestamp, direction, kind, frame);
+    }
+}
+
+impl<I, O, C, Ts> Interface for TracingInterface<I, O, C>
+where
+    I: Interface,
+    O: Observer<Ts>,
+    C: FnMut() -> Ts,
+{
+    type Error = I::Error;
+
+    fn write(&mut self, frame: &mut [u8]) -> Result<(), Self::Error> {
+        self.trace(Direction::HostToPn532, frame);
+        self.interface.write(frame)
+    }
+
+    fn wait_ready(&mut self) -> Poll<Result<(), Self::Error>> {
+        self.interface.wait_ready()
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.interface.read(buf)?;
+        self.trace(Direction::Pn532ToHost, buf);
+        Ok(())
+    }
+
+    fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = self.interface.read_frame(buf)?;
+        self.trace(Direction::Pn532ToHost, &buf[..len]);
+        Ok(len)
+    }
+
+    fn continue_frame(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Self::Error> {
+        let continued_len = self.interface.continue_frame(buf, len)?;
+        if continued_len > len {
+            self.trace(Direction::Pn532ToHost, &buf[len..continued_len]);
+        }
+        Ok(continued_len)
+    }
+}
+
+/// [`Observer`] that logs every frame with [`log::debug!`]
+#[cfg(feature = "log")]
+#[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LogObserver;
+
+#[cfg(feature = "log")]
+impl<Ts: core::fmt::Debug> Observer<Ts> for LogObserver {
+    fn observe(&mut self, timestamp: Ts, direction: Direction, kind: FrameKind, frame: &[u8]) {
+        log::debug!("{timestamp:?} {direction:?} {kind:?}: {frame:02X?}");
+    }
+}
+
+/// [`Observer`] that logs every frame with [`defmt::debug!`]
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DefmtObserver;
+
+#[cfg(feature = "defmt")]
+impl<Ts: defmt::Format> Observer<Ts> for DefmtObserver {
+    fn observe(&mut self, timestamp: Ts, direction: Direction, kind: FrameKind, frame: &[u8]) {
+        defmt::debug!("{} {} {}: {=[u8]:02X}", timestamp, direction, kind, frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc_test_helper::NoOpTimer;
+    use crate::protocol::tests::{FrameInterface, FIRMWARE_VERSION_RESPONSE};
+    use crate::protocol::ACK;
+    use crate::{Pn532, Request};
+    use core::time::Duration;
+
+    #[test]
+    fn test_frame_kind() {
+        use Direction::*;
+        assert_eq!(FrameKind::of(Pn532ToHost, &ACK), FrameKind::Ack);
+        let nack = [0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00];
+        assert_eq!(FrameKind::of(HostToPn532, &nack), FrameKind::Nack);
+        let extended = [0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x01, 0x00, 0xFF];
+        assert_eq!(FrameKind::of(HostToPn532, &extended), FrameKind::Command);
+        assert_eq!(FrameKind::of(Pn532ToHost, &[0x00; 8]), FrameKind::Unknown);
+    }
+
+    #[test]
+    fn test_tracing_interface() {
+        let mut frames = Vec::new();
+        let mut time = 0;
+        let interface = TracingInterface {
+            interface: FrameInterface {
+                written: Vec::new(),
+                reads: vec![ACK.to_vec(), FIRMWARE_VERSION_RESPONSE.to_vec()].into(),
+            },
+            observer: |timestamp, direction, kind, frame: &[u8]| {
+                frames.push((timestamp, direction, kind, frame.len()))
+            },
+            clock: || {
+                time += 1;
+                time
+            },
+        };
+        let mut pn532 = Pn532::<_, _, 32>::new(interface, NoOpTimer);
+        pn532
+            .process_auto(&Request::GET_FIRMWARE_VERSION, Duration::ZERO)
+            .unwrap();
+        drop(pn532);
+        assert_eq!(
+            frames,
+            [
+                (1, Direction::HostToPn532, FrameKind::Command, 9),
+                (2, Direction::Pn532ToHost, FrameKind::Ack, 6),
+                (3, Direction::Pn532ToHost, FrameKind::Response, 13),
+            ]
+        );
+    }
+}
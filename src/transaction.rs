@@ -0,0 +1,171 @@
+use crate::protocol::Error;
+use crate::requests::{Command, IntoRequest};
+use crate::{Interface, Pn532};
+use core::ops::Range;
+use core::task::Poll;
+
+/// Progress of a [`Transaction`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TransactionState {
+    /// The request was sent, waiting for the ACK frame
+    Sent,
+    /// The ACK frame was received, waiting for the response frame
+    AckReceived,
+    /// The response frame was received
+    ResponseReady,
+    /// Receiving the ACK or the response frame failed, the link may be out of sync
+    Failed,
+}
+
+/// A request that is processed step by step with [`poll`](Transaction::poll),
+/// created by [`Pn532::start`]
+///
+/// Each call to `poll` checks [`Interface::wait_ready`] and never blocks,
+/// so other work can be done between the calls.
+#[derive(Debug)]
+pub struct Transaction<'p, I, T, const N: usize> {
+    pn532: &'p mut Pn532<I, T, N>,
+    sent_command: Command,
+    response_len: usize,
+    state: TransactionState,
+    response: Range<usize>,
+}
+
+impl<I: Interface, T, const N: usize> Pn532<I, T, N> {
+    /// Send a request and return a [`Transaction`] that receives the ACK and the response
+    /// when it is polled.
+    ///
+    /// `response_len` is the largest expected length of the returned data.
+    ///
+    /// ```
+    /// # use pn532::doc_test_helper::get_pn532;
+    /// use core::task::Poll;
+    /// use pn532::Request;
+    ///
+    /// let mut pn532 = get_pn532();
+    /// let mut transaction = pn532.start(&Request::GET_FIRMWARE_VERSION, 4).unwrap();
+    /// // do something else
+    /// if let Poll::Ready(Ok(response)) = transaction.poll() {
+    ///     println!("firmware version: {:?}", response);
+    /// }
+    /// ```
+    pub fn start<'a>(
+        &mut self,
+        request: impl IntoRequest<'a, N>,
+        response_len: usize,
+    ) -> Result<Transaction<'_, I, T, N>, Error<I::Error>> {
+        let request = request.into_request();
+        let sent_command = request.command;
+        self.send(request)?;
+        Ok(Transaction {
+            pn532: self,
+            sent_command,
+            response_len,
+            state: TransactionState::Sent,
+            response: 0..0,
+        })
+    }
+}
+
+impl<I: Interface, T, const N: usize> Transaction<'_, I, T, N> {
+    /// Receives the ACK frame and the response frame as soon as the interface is ready.
+    ///
+    /// Returns the response data once the response frame was received.
+    ///
+    /// After an error the transaction is [`Failed`](TransactionState::Failed) and every further call
+    /// returns [`Error::TransactionFailed`] without touching the interface.
+    /// [`abort`](Transaction::abort) it and start a new one.
+    pub fn poll(&mut self) -> Poll<Result<&[u8], Error<I::Error>>> {
+        match self.step() {
+            Poll::Ready(Ok(())) => Poll::Ready(Ok(&self.pn532.buf[self.response.clone()])),
+            Poll::Ready(Err(e)) => {
+                self.state = TransactionState::Failed;
+                Poll::Ready(Err(e))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn step(&mut self) -> Poll<Result<(), Error<I::Error>>> {
+        if self.state == TransactionState::Failed {
+            return Poll::Ready(Err(Error::TransactionFailed));
+        }
+        if self.state == TransactionState::Sent {
+            if self.pn532.interface.wait_ready()?.is_pending() {
+                return Poll::Pending;
+            }
+            self.pn532.receive_ack()?;
+            self.state = TransactionState::AckReceived;
+        }
+        if self.state == TransactionState::AckReceived {
+            if self.pn532.interface.wait_ready()?.is_pending() {
+                return Poll::Pending;
+            }
+            self.response = self
+                .pn532
+                .receive_response_range(self.sent_command, Some(self.response_len))?;
+            self.state = TransactionState::ResponseReady;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Current progress of the transaction
+    pub fn state(&self) -> TransactionState {
+        self.state
+    }
+
+    /// Send an ACK frame to abort the transaction, see [`Pn532::abort`].
+    pub fn abort(self) -> Result<(), Error<I::Error>> {
+        self.pn532.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::tests::{FrameInterface, FIRMWARE_VERSION_RESPONSE};
+    use crate::protocol::ACK;
+    use crate::Request;
+
+    #[test]
+    fn test_transaction() {
+        let mut pn532 = Pn532::<_, _, 32>::new(FrameInterface::default(), ());
+        let mut transaction = pn532.start(&Request::GET_FIRMWARE_VERSION, 4).unwrap();
+        assert!(transaction.poll().is_pending());
+        assert_eq!(transaction.state(), TransactionState::Sent);
+
+        transaction.pn532.interface.reads.push_back(ACK.to_vec());
+        assert!(transaction.poll().is_pending());
+        assert_eq!(transaction.state(), TransactionState::AckReceived);
+
+        let response = FIRMWARE_VERSION_RESPONSE.to_vec();
+        transaction.pn532.interface.reads.push_back(response);
+        assert_eq!(
+            transaction.poll(),
+            Poll::Ready(Ok(&[0x32, 0x01, 0x06, 0x07][..]))
+        );
+        assert_eq!(transaction.state(), TransactionState::ResponseReady);
+        assert_eq!(
+            transaction.poll(),
+            Poll::Ready(Ok(&[0x32, 0x01, 0x06, 0x07][..]))
+        );
+    }
+
+    #[test]
+    fn test_transaction_failed() {
+        let mut pn532 = Pn532::<_, _, 32>::new(FrameInterface::default(), ());
+        let mut transaction = pn532.start(&Request::GET_FIRMWARE_VERSION, 4).unwrap();
+        transaction.pn532.interface.reads.push_back(vec![0xAA; 6]);
+        assert_eq!(transaction.poll(), Poll::Ready(Err(Error::BadAck)));
+        assert_eq!(transaction.state(), TransactionState::Failed);
+
+        // the response is not read on the desynchronised link
+        let response = FIRMWARE_VERSION_RESPONSE.to_vec();
+        transaction.pn532.interface.reads.push_back(response);
+        assert_eq!(
+            transaction.poll(),
+            Poll::Ready(Err(Error::TransactionFailed))
+        );
+        assert_eq!(transaction.pn532.interface.reads.len(), 1);
+    }
+}